@@ -0,0 +1,100 @@
+use crate::analysis::series::Series;
+use crate::cli::SortBy;
+use crate::models::{Correlation, ExchangeRateResult};
+
+/// Computes the Pearson correlation of the daily log returns, for every pair of currencies.
+/// Only the days where both currencies have a return are compared.
+pub fn correlate(
+    exchange_rate_results: &[ExchangeRateResult],
+    sort_by: &SortBy,
+) -> Vec<Correlation> {
+    let returns = Series::new(exchange_rate_results, sort_by)
+        .rates
+        .into_iter()
+        .map(|(currency, values)| {
+            let returns = values
+                .windows(2)
+                .map(|window| match window {
+                    [Some(previous), Some(rate)] => Some((rate / previous).ln()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            (currency, returns)
+        })
+        .collect::<Vec<_>>();
+
+    returns
+        .iter()
+        .map(|(currency, a)| Correlation {
+            currency: currency.clone(),
+            correlations: returns
+                .iter()
+                .map(|(other, b)| (other.clone(), pearson(a, b)))
+                .collect(),
+        })
+        .collect()
+}
+
+fn pearson(a: &[Option<f64>], b: &[Option<f64>]) -> Option<f64> {
+    let pairs = a
+        .iter()
+        .zip(b.iter())
+        .filter_map(|pair| match pair {
+            (Some(a), Some(b)) => Some((*a, *b)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let count = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|(a, _)| a).sum::<f64>() / count;
+    let mean_b = pairs.iter().map(|(_, b)| b).sum::<f64>() / count;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (a, b) in pairs {
+        covariance += (a - mean_a) * (b - mean_b);
+        variance_a += (a - mean_a).powi(2);
+        variance_b += (b - mean_b).powi(2);
+    }
+    if variance_a == 0.0 || variance_b == 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_a * variance_b).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_correlate() {
+        let exchange_rate_results = [
+            ("2025-01-07", 1.0, 1.0, 1.0),
+            ("2025-01-08", 2.0, 0.5, 1.0),
+            ("2025-01-09", 1.0, 1.0, 1.0),
+            ("2025-01-10", 4.0, 0.25, 1.0),
+        ]
+        .into_iter()
+        .map(|(time, usd, sek, dkk)| {
+            let rates = HashMap::from([
+                ("USD".into(), usd),
+                ("SEK".into(), sek),
+                ("DKK".into(), dkk),
+            ]);
+            ExchangeRateResult::new(time.into(), rates)
+        })
+        .collect::<Vec<_>>();
+
+        let correlations = correlate(&exchange_rate_results, &SortBy::Currency);
+        let currencies = correlations.iter().map(|x| x.currency.as_str());
+        assert_eq!(currencies.collect::<Vec<_>>(), ["DKK", "SEK", "USD"]);
+
+        let usd = &correlations[2].correlations;
+        assert!((usd["USD"].unwrap() - 1.0).abs() < 1e-12);
+        assert!((usd["SEK"].unwrap() + 1.0).abs() < 1e-12);
+        // The DKK never moves
+        assert_eq!(usd["DKK"], None);
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use clap::ValueEnum;
+use smol_str::SmolStr;
+
+use crate::models::ExchangeRateResult;
+use crate::utils_calc::filter_date_range;
+use crate::{DateRange, Hollidays, TargetCalendar};
+
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum FillPolicy {
+    /// Repeat the rates of the publication before the gap
+    #[value(name = "ffill")]
+    Forward,
+    /// Use the rates of the publication after the gap
+    #[value(name = "bfill")]
+    Backward,
+    /// Interpolate linearly between the publications around the gap
+    Linear,
+}
+
+/// Adds a result for every calendar day between two publications, marked as filled, and keeps
+/// the days within `range`. Its edges are filled from the publications around it, see
+/// [neighbours]. Forward filling also continues after the last publication up to the end of the
+/// range, over the days on which nothing is published.
+/// The results are in chronological order.
+pub fn fill_gaps(
+    exchange_rate_results: &[ExchangeRateResult],
+    policy: FillPolicy,
+    range: &DateRange,
+) -> Vec<ExchangeRateResult> {
+    let mut days = exchange_rate_results
+        .iter()
+        .filter_map(|exchange_rate| {
+            let date = exchange_rate.time.parse::<NaiveDate>().ok()?;
+            Some((date, exchange_rate))
+        })
+        .collect::<Vec<_>>();
+    days.sort_by_key(|(date, _)| *date);
+
+    let mut filled = Vec::with_capacity(exchange_rate_results.len());
+    for (i, (date, exchange_rate)) in days.iter().enumerate() {
+        filled.push((*exchange_rate).clone());
+        let Some((next_date, next)) = days.get(i + 1) else {
+            break;
+        };
+
+        let gap = (*next_date - *date).num_days();
+        for (day, missing) in date
+            .iter_days()
+            .skip(1)
+            .take_while(|x| x < next_date)
+            .zip(1..)
+        {
+            let rates = match policy {
+                FillPolicy::Forward => exchange_rate.rates.clone(),
+                FillPolicy::Backward => next.rates.clone(),
+                FillPolicy::Linear => {
+                    let weight = missing as f64 / gap as f64;
+                    exchange_rate
+                        .rates
+                        .iter()
+                        .filter_map(|(currency, rate)| {
+                            let next_rate = next.rates.get(currency)?;
+                            Some((currency.clone(), rate + (next_rate - rate) * weight))
+                        })
+                        .collect()
+                }
+            };
+            filled.push(filled_day(day, rates));
+        }
+    }
+
+    if let (FillPolicy::Forward, Some((date, last)), Some(to)) = (policy, days.last(), range.to) {
+        let unpublished = date
+            .iter_days()
+            .skip(1)
+            .take_while(|day| day <= &to && !TargetCalendar::is_publication_day(day));
+        for day in unpublished {
+            filled.push(filled_day(day, last.rates.clone()));
+        }
+    }
+    filter_date_range(&mut filled, range);
+    filled
+}
+
+/// The publications in `history` right before and after `range`, which its edges are filled from
+pub fn neighbours(history: &[ExchangeRateResult], range: &DateRange) -> Vec<ExchangeRateResult> {
+    let days = history.iter().filter_map(|exchange_rate| {
+        let date = exchange_rate.time.parse::<NaiveDate>().ok()?;
+        Some((date, exchange_rate))
+    });
+    let before = range.from.and_then(|from| {
+        days.clone()
+            .filter(|(date, _)| date < &from)
+            .max_by_key(|(date, _)| *date)
+    });
+    let after = range.to.and_then(|to| {
+        days.filter(|(date, _)| date > &to)
+            .min_by_key(|(date, _)| *date)
+    });
+    before
+        .into_iter()
+        .chain(after)
+        .map(|(_, exchange_rate)| exchange_rate.clone())
+        .collect()
+}
+
+fn filled_day(day: NaiveDate, rates: HashMap<SmolStr, f64>) -> ExchangeRateResult {
+    let mut exchange_rate = ExchangeRateResult::new(day.to_string().into(), rates);
+    exchange_rate.filled = true;
+    exchange_rate.reason = Some(gap_reason(&day));
+    exchange_rate
+}
+
+/// Why the ECB didn't publish any rates on the given date
+fn gap_reason(date: &NaiveDate) -> SmolStr {
+    if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        "weekend".into()
+    } else if let Some(name) = Hollidays::new(date.year()).name(date) {
+        name.into()
+    } else if TargetCalendar::new(date.year()).is_some_and(|x| x.is_closing_day(date)) {
+        "TARGET closing day".into()
+    } else {
+        "not published".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_gaps() {
+        // Easter 2024, from Good Friday to Easter Monday
+        let exchange_rate_results = [("2024-04-02", 0.0), ("2024-03-28", 5.0)]
+            .into_iter()
+            .map(|(time, rate)| {
+                ExchangeRateResult::new(time.into(), HashMap::from([("USD".into(), rate)]))
+            })
+            .collect::<Vec<_>>();
+        let rates = |policy| {
+            fill_gaps(&exchange_rate_results, policy, &DateRange::default())
+                .into_iter()
+                .map(|x| (x.time, x.rates["USD"], x.reason))
+                .collect::<Vec<_>>()
+        };
+
+        let linear = rates(FillPolicy::Linear);
+        assert_eq!(
+            linear,
+            [
+                ("2024-03-28".into(), 5.0, None),
+                ("2024-03-29".into(), 4.0, Some("Good Friday".into())),
+                ("2024-03-30".into(), 3.0, Some("weekend".into())),
+                ("2024-03-31".into(), 2.0, Some("weekend".into())),
+                ("2024-04-01".into(), 1.0, Some("Easter Monday".into())),
+                ("2024-04-02".into(), 0.0, None),
+            ]
+        );
+        assert_eq!(rates(FillPolicy::Forward)[3].1, 5.0);
+        assert_eq!(rates(FillPolicy::Backward)[1].1, 0.0);
+    }
+
+    fn publications(dates: &[&str]) -> Vec<ExchangeRateResult> {
+        dates
+            .iter()
+            .zip(1..)
+            .map(|(time, rate)| {
+                ExchangeRateResult::new(
+                    (*time).into(),
+                    HashMap::from([("USD".into(), rate as f64)]),
+                )
+            })
+            .collect()
+    }
+
+    fn times(exchange_rate_results: &[ExchangeRateResult]) -> Vec<&str> {
+        exchange_rate_results
+            .iter()
+            .map(|x| x.time.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_window_starting_on_a_weekend() {
+        let history = publications(&["2026-10-13", "2026-10-12", "2026-10-09"]);
+        let range = DateRange::new("2026-10-10".parse().ok(), "2026-10-13".parse().ok());
+        let mut exchange_rate_results = history[..2].to_vec();
+        exchange_rate_results.extend(neighbours(&history, &range));
+        assert_eq!(times(&neighbours(&history, &range)), ["2026-10-09"]);
+
+        let filled = fill_gaps(&exchange_rate_results, FillPolicy::Forward, &range);
+        assert_eq!(
+            times(&filled),
+            ["2026-10-10", "2026-10-11", "2026-10-12", "2026-10-13"]
+        );
+        assert!(filled[0].filled);
+        assert_eq!(filled[0].rates["USD"], 3.0, "friday");
+    }
+
+    #[test]
+    fn test_window_ending_on_a_weekend() {
+        let exchange_rate_results = publications(&["2026-10-16", "2026-10-15"]);
+        let range = DateRange::new("2026-10-15".parse().ok(), "2026-10-18".parse().ok());
+        let filled = fill_gaps(&exchange_rate_results, FillPolicy::Forward, &range);
+        assert_eq!(
+            times(&filled),
+            ["2026-10-15", "2026-10-16", "2026-10-17", "2026-10-18"]
+        );
+        assert_eq!(filled[3].rates["USD"], 1.0, "friday");
+
+        // Nothing is filled in for a day on which rates are yet to be published
+        let range = DateRange::new(range.from, "2026-10-19".parse().ok());
+        let filled = fill_gaps(&exchange_rate_results, FillPolicy::Forward, &range);
+        assert_eq!(times(&filled).last(), Some(&"2026-10-18"));
+    }
+}
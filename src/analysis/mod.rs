@@ -0,0 +1,10 @@
+mod correlate;
+mod fill;
+mod resample;
+mod series;
+mod stats;
+
+pub use correlate::correlate;
+pub use fill::{FillPolicy, fill_gaps, neighbours};
+pub use resample::{Aggregate, Period, resample};
+pub use stats::stats;
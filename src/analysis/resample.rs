@@ -0,0 +1,183 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+use smol_str::{SmolStr, format_smolstr};
+
+use crate::models::{Aggregates, ExchangeRateResult};
+
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// ISO weeks, e.g. 2025-W02
+    Week,
+    /// Calendar months, e.g. 2025-01
+    Month,
+    /// Calendar quarters, e.g. 2025-Q1
+    Quarter,
+    /// Calendar years, e.g. 2025
+    Year,
+}
+
+impl Period {
+    /// The label of the period which contains the date. Labels sort in chronological order.
+    pub fn label(&self, date: &NaiveDate) -> SmolStr {
+        match self {
+            Self::Week => {
+                let week = date.iso_week();
+                format_smolstr!("{}-W{:02}", week.year(), week.week())
+            }
+            Self::Month => format_smolstr!("{}-{:02}", date.year(), date.month()),
+            Self::Quarter => format_smolstr!("{}-Q{}", date.year(), date.quarter()),
+            Self::Year => format_smolstr!("{}", date.year()),
+        }
+    }
+}
+
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    /// The average rate of the period
+    Mean,
+    /// The last rate of the period
+    Close,
+    /// The first rate of the period
+    Open,
+    /// The highest rate of the period
+    High,
+    /// The lowest rate of the period
+    Low,
+}
+
+/// Groups the publications by period, and reduces the rates of each currency to every aggregate.
+/// The rates are the chosen aggregate, and the time is the last publication in the period, so
+/// the results can still be rendered as dated prices. The results are in chronological order.
+pub fn resample(
+    exchange_rate_results: &[ExchangeRateResult],
+    period: Period,
+    aggregate: Aggregate,
+) -> Vec<ExchangeRateResult> {
+    let mut periods: BTreeMap<SmolStr, Vec<(NaiveDate, &ExchangeRateResult)>> = BTreeMap::new();
+    for exchange_rate in exchange_rate_results {
+        if let Ok(date) = exchange_rate.time.parse::<NaiveDate>() {
+            periods
+                .entry(period.label(&date))
+                .or_default()
+                .push((date, exchange_rate));
+        }
+    }
+
+    periods
+        .into_iter()
+        .filter_map(|(label, mut days)| {
+            days.sort_by_key(|(date, _)| *date);
+            let (_, last) = days.last()?;
+            let time = last.time.clone();
+
+            let mut rates: HashMap<SmolStr, Vec<f64>> = HashMap::new();
+            for (_, exchange_rate) in days {
+                for (currency, rate) in exchange_rate.rates.iter() {
+                    rates.entry(currency.clone()).or_default().push(*rate);
+                }
+            }
+            let aggregates = rates
+                .into_iter()
+                .map(|(currency, rates)| (currency, reduce(&rates)))
+                .collect::<HashMap<_, _>>();
+
+            let rates = aggregates
+                .iter()
+                .map(|(currency, aggregates)| (currency.clone(), aggregates.get(aggregate)))
+                .collect();
+            let mut exchange_rate = ExchangeRateResult::new(time, rates);
+            exchange_rate.period = Some(label);
+            exchange_rate.aggregates = aggregates;
+            Some(exchange_rate)
+        })
+        .collect()
+}
+
+/// Reduces the chronological rates of a period, which is never empty
+fn reduce(rates: &[f64]) -> Aggregates {
+    Aggregates {
+        mean: rates.iter().sum::<f64>() / rates.len() as f64,
+        close: rates[rates.len() - 1],
+        open: rates[0],
+        high: rates.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        low: rates.iter().copied().fold(f64::INFINITY, f64::min),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample() {
+        let exchange_rate_results = ["2025-01-31", "2024-12-31", "2025-01-02", "2025-02-03"]
+            .into_iter()
+            .zip([3.0, 1.0, 2.0, 4.0])
+            .map(|(time, rate)| {
+                ExchangeRateResult::new(time.into(), HashMap::from([("USD".into(), rate)]))
+            })
+            .collect::<Vec<_>>();
+        let rates = |period, aggregate| {
+            resample(&exchange_rate_results, period, aggregate)
+                .into_iter()
+                .map(|x| (x.period.unwrap(), x.rates["USD"]))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            rates(Period::Month, Aggregate::Mean),
+            [
+                ("2024-12".into(), 1.0),
+                ("2025-01".into(), 2.5),
+                ("2025-02".into(), 4.0)
+            ]
+        );
+        assert_eq!(
+            rates(Period::Quarter, Aggregate::Close),
+            [("2024-Q4".into(), 1.0), ("2025-Q1".into(), 4.0)]
+        );
+        assert_eq!(
+            rates(Period::Year, Aggregate::Open),
+            [("2024".into(), 1.0), ("2025".into(), 2.0)]
+        );
+        // 2024-12-31 belongs to the first ISO week of 2025
+        assert_eq!(
+            rates(Period::Week, Aggregate::High),
+            [
+                ("2025-W01".into(), 2.0),
+                ("2025-W05".into(), 3.0),
+                ("2025-W06".into(), 4.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resample_keeps_the_last_date_and_every_aggregate() {
+        let exchange_rate_results = [
+            ("2025-01-02", 2.0),
+            ("2025-01-31", 1.0),
+            ("2025-01-15", 6.0),
+        ]
+        .into_iter()
+        .map(|(time, rate)| {
+            ExchangeRateResult::new(time.into(), HashMap::from([("USD".into(), rate)]))
+        })
+        .collect::<Vec<_>>();
+        let month = &resample(&exchange_rate_results, Period::Month, Aggregate::Low)[0];
+        assert_eq!(month.time, "2025-01-31");
+        assert_eq!(month.label(), "2025-01");
+        assert_eq!(month.rates["USD"], 1.0);
+        assert_eq!(
+            month.aggregates["USD"],
+            Aggregates {
+                mean: 3.0,
+                close: 1.0,
+                open: 2.0,
+                high: 6.0,
+                low: 1.0
+            }
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use smol_str::SmolStr;
+
+use crate::cli::SortBy;
+use crate::models::ExchangeRateResult;
+
+/// The rates of every currency over the days, lined up by day so that they can be compared
+pub(crate) struct Series {
+    /// The label of every day, or resampled period, in chronological order
+    pub labels: Vec<SmolStr>,
+    /// Every currency, with its rate on each of the days, or None if it wasn't published then
+    pub rates: Vec<(SmolStr, Vec<Option<f64>>)>,
+}
+
+impl Series {
+    pub fn new(exchange_rate_results: &[ExchangeRateResult], sort_by: &SortBy) -> Self {
+        let days = exchange_rate_results
+            .iter()
+            .map(|exchange_rate| (exchange_rate.time.as_str(), exchange_rate))
+            .collect::<BTreeMap<_, _>>();
+        let currencies = days
+            .values()
+            .flat_map(|exchange_rate| exchange_rate.rates.keys())
+            .collect::<BTreeSet<_>>();
+
+        let mut rates = currencies
+            .into_iter()
+            .map(|currency| {
+                let values = days
+                    .values()
+                    .map(|exchange_rate| exchange_rate.rates.get(currency).copied())
+                    .collect::<Vec<_>>();
+                (currency.clone(), values)
+            })
+            .collect::<Vec<_>>();
+        if let SortBy::Rate = sort_by {
+            // By the rate on the first day, like the tables
+            rates.sort_by(|a, b| {
+                let a = a.1.first().copied().flatten().unwrap_or(f64::INFINITY);
+                let b = b.1.first().copied().flatten().unwrap_or(f64::INFINITY);
+                a.total_cmp(&b)
+            });
+        }
+
+        Self {
+            labels: days.values().map(|x| x.label().into()).collect(),
+            rates,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::analysis::series::Series;
+use crate::cli::SortBy;
+use crate::models::{ExchangeRateResult, Observation, Stats};
+
+/// Computes the summary statistics of every currency, over all of the given days
+pub fn stats(exchange_rate_results: &[ExchangeRateResult], sort_by: &SortBy) -> Vec<Stats> {
+    let series = Series::new(exchange_rate_results, sort_by);
+    series
+        .rates
+        .iter()
+        .filter_map(|(currency, values)| {
+            let observations = values
+                .iter()
+                .zip(series.labels.iter())
+                .filter_map(|(value, time)| {
+                    value.map(|rate| Observation {
+                        time: time.clone(),
+                        rate,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let first = observations.first()?.clone();
+            let last = observations.last()?.clone();
+            // The first one wins a tie
+            let min = observations
+                .iter()
+                .reduce(|min, x| if x.rate < min.rate { x } else { min })?
+                .clone();
+            let max = observations
+                .iter()
+                .reduce(|max, x| if x.rate > max.rate { x } else { max })?
+                .clone();
+
+            let mut rates = observations.iter().map(|x| x.rate).collect::<Vec<_>>();
+            let count = rates.len();
+            let mean = rates.iter().sum::<f64>() / count as f64;
+            let stddev = if count > 1 {
+                let squares = rates.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+                (squares / (count - 1) as f64).sqrt()
+            } else {
+                0.0
+            };
+            rates.sort_by(f64::total_cmp);
+            let median = if count % 2 == 0 {
+                (rates[count / 2 - 1] + rates[count / 2]) / 2.0
+            } else {
+                rates[count / 2]
+            };
+
+            Some(Stats {
+                currency: currency.clone(),
+                count,
+                change: last.rate - first.rate,
+                change_percent: (last.rate / first.rate - 1.0) * 100.0,
+                first,
+                last,
+                min,
+                max,
+                mean,
+                median,
+                stddev,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_stats() {
+        let exchange_rate_results = ["2025-01-09", "2025-01-07", "2025-01-08", "2025-01-10"]
+            .into_iter()
+            .zip([4.0, 2.0, 4.0, 5.0])
+            .map(|(time, rate)| {
+                ExchangeRateResult::new(time.into(), HashMap::from([("USD".into(), rate)]))
+            })
+            .collect::<Vec<_>>();
+
+        let stats = stats(&exchange_rate_results, &SortBy::Currency);
+        assert_eq!(stats.len(), 1);
+        let usd = &stats[0];
+        assert_eq!(usd.count, 4);
+        assert_eq!(usd.first.time, "2025-01-07");
+        assert_eq!(usd.last.rate, 5.0);
+        assert_eq!(usd.min.time, "2025-01-07");
+        assert_eq!(usd.max.time, "2025-01-10");
+        assert_eq!(usd.mean, 3.75);
+        assert_eq!(usd.median, 4.0);
+        assert!((usd.stddev - (4.75f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(usd.change, 3.0);
+        assert_eq!(usd.change_percent, 150.0);
+    }
+}
@@ -2,10 +2,26 @@ use std::fs;
 use std::io::{BufReader, BufWriter};
 use std::path::{Path, PathBuf};
 
+use chrono::serde::ts_seconds;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
 use super::CacheLine;
 use crate::View;
+use crate::models::ExchangeRateResult;
 use crate::os::Os;
 
+const FILE_NAME: &str = "history.json";
+
+/// The cache of older versions, which kept a file per view
+#[derive(Deserialize)]
+struct LegacyCacheLine {
+    #[serde(with = "ts_seconds")]
+    date: DateTime<Utc>,
+    #[serde(rename = "camelCase")]
+    exchange_rate_results: Vec<ExchangeRateResult>,
+}
+
 #[derive(Debug)]
 pub struct Cache {
     cache_line: Option<CacheLine>,
@@ -13,37 +29,43 @@ pub struct Cache {
 }
 
 impl Cache {
-    pub fn load(view: &View) -> Option<Self> {
+    pub fn load() -> Option<Self> {
         let config_opt = Os::get_current()?.get_config_path();
-        let mut config_path = match config_opt {
-            Ok(k) => k,
+        match config_opt {
+            Ok(config_path) => Self::load_from(config_path),
             Err(e) => {
                 eprintln!("Failed to locate config dir: {:?}", e);
-                return None;
+                None
             }
-        };
+        }
+    }
+
+    /// Loads the cache kept in the given directory, creating the directory if needed
+    pub fn load_from(mut config_path: PathBuf) -> Option<Self> {
         if let Err(e) = fs::create_dir_all(&config_path) {
             eprintln!("Failed to create config dir: {:?}", e);
             return None;
         }
-        config_path.push(format!("{}.json", view.get_name()));
-        if !config_path.try_exists().unwrap_or_default() {
-            return Some(Self {
-                cache_line: None,
-                config_path,
-            });
-        }
-
-        match Self::read_config(&config_path) {
-            Ok(cache_line) => Some(Self {
-                cache_line: Some(cache_line),
-                config_path,
-            }),
-            Err(e) => {
-                eprintln!("Config path is invalid, or cannot be created: {:?}", e);
-                None
+        let config_dir = config_path.clone();
+        config_path.push(FILE_NAME);
+        let cache_line = if config_path.try_exists().unwrap_or_default() {
+            match Self::read_config(&config_path) {
+                Ok(cache_line) => Some(cache_line),
+                Err(e) => {
+                    eprintln!("Config path is invalid, or cannot be created: {:?}", e);
+                    return None;
+                }
             }
-        }
+        } else {
+            None
+        };
+
+        let mut cache = Self {
+            cache_line,
+            config_path,
+        };
+        cache.import_legacy_files(&config_dir);
+        Some(cache)
     }
 
     pub fn get_cache_line(&self) -> Option<&CacheLine> {
@@ -54,7 +76,15 @@ impl Cache {
         self.cache_line = Some(cache_line);
     }
 
-    pub fn save(&self) -> anyhow::Result<()> {
+    /// Merges freshly fetched publications from `view` into the cached history
+    pub fn merge(&mut self, view: View, exchange_rate_results: Vec<ExchangeRateResult>) {
+        match self.cache_line.as_mut() {
+            Some(cache_line) => cache_line.merge(view, exchange_rate_results),
+            None => self.cache_line = Some(CacheLine::new(view, exchange_rate_results)),
+        }
+    }
+
+    pub fn save(&self) -> crate::Result<()> {
         let file = fs::File::options()
             .write(true)
             .create(true)
@@ -67,9 +97,97 @@ impl Cache {
         Ok(())
     }
 
-    fn read_config(path: &Path) -> anyhow::Result<CacheLine> {
+    fn read_config(path: &Path) -> crate::Result<CacheLine> {
         let file = fs::File::open(path)?;
         let reader = BufReader::new(file);
         Ok(serde_json::from_reader(reader)?)
     }
+
+    /// Older versions kept one file per view, which are merged into the history.
+    /// They are only removed once the history has been saved, and left in place if unreadable.
+    fn import_legacy_files(&mut self, config_dir: &Path) {
+        let mut imported = Vec::new();
+        for view in [View::HistDaysAll, View::HistDays90, View::TODAY] {
+            let legacy_path = config_dir.join(format!("{}.json", view.get_name()));
+            if !legacy_path.is_file() {
+                continue;
+            }
+            let legacy = match Self::read_legacy(&legacy_path) {
+                Ok(legacy) => legacy,
+                Err(e) => {
+                    eprintln!(
+                        "Failed to import the old cache {}: {:?}",
+                        legacy_path.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+            let cache_line = self
+                .cache_line
+                .get_or_insert_with(|| CacheLine::new(view, Vec::new()));
+            cache_line.merge_fetched_at(view, legacy.exchange_rate_results, legacy.date);
+            imported.push(legacy_path);
+        }
+        if imported.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.save() {
+            eprintln!("Failed to save the imported old cache: {:?}", e);
+            return;
+        }
+        for legacy_path in imported {
+            match fs::remove_file(&legacy_path) {
+                Ok(()) => eprintln!(
+                    "Imported and removed the old cache {}",
+                    legacy_path.display()
+                ),
+                Err(e) => eprintln!(
+                    "Failed to remove the old cache {}: {:?}",
+                    legacy_path.display(),
+                    e
+                ),
+            }
+        }
+    }
+
+    fn read_legacy(path: &Path) -> crate::Result<LegacyCacheLine> {
+        let file = fs::File::open(path)?;
+        let reader = BufReader::new(file);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDir;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_import_legacy_files() {
+        let dir = TestDir::new("legacy");
+        fs::create_dir_all(&dir.0).unwrap();
+        let legacy_path = dir.0.join("last-90-days.json");
+        let legacy =
+            r#"{"date":1736524800,"camelCase":[{"time":"2025-01-10","rates":{"USD":1.03}}]}"#;
+        fs::write(&legacy_path, legacy).unwrap();
+        let unreadable_path = dir.0.join("today.json");
+        fs::write(&unreadable_path, "{").unwrap();
+
+        let cache = Cache::load_from(dir.0.clone()).unwrap();
+        let cache_line = cache.get_cache_line().unwrap();
+        let imported = cache_line.get(&View::HistDays90).unwrap();
+        assert_eq!(imported[0].time, "2025-01-10");
+        let fetched_at = Utc.with_ymd_and_hms(2025, 1, 10, 16, 0, 0).unwrap();
+        assert_eq!(cache_line.fetched_at(), fetched_at);
+
+        assert!(!legacy_path.exists());
+        assert!(
+            unreadable_path.exists(),
+            "unreadable files are left in place"
+        );
+        assert!(dir.0.join(FILE_NAME).is_file());
+    }
 }
@@ -1,45 +1,57 @@
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 use chrono::serde::ts_seconds;
-use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, TimeDelta, Utc, Weekday};
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeDelta, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
 
-use crate::Hollidays;
 use crate::models::ExchangeRateResult;
+use crate::{Frankfurt, TargetCalendar, View};
 
-const CET: FixedOffset = unsafe { FixedOffset::east_opt(3600).unwrap_unchecked() };
-
+/// The merged history of every feed fetched so far, keyed by the date of publication
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct CacheLine {
+    /// The last time anything was fetched from the ECB
     #[serde(with = "ts_seconds")]
     date: DateTime<Utc>,
 
-    #[serde(rename = "camelCase")]
-    pub exchange_rate_results: Vec<ExchangeRateResult>,
+    days: BTreeMap<NaiveDate, HashMap<SmolStr, f64>>,
+
+    /// Spans of dates in which no publication is missing, ordered from oldest to newest
+    coverage: Vec<Coverage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct Coverage {
+    from: NaiveDate,
+    to: NaiveDate,
+    /// The largest view which is contained in the span, had it been fetched on `to`
+    reach: View,
 }
 
 impl CacheLine {
     pub fn is_valid(&self) -> bool {
-        self.is_valid_at(Local::now().with_timezone(&CET))
+        self.is_valid_at(Frankfurt::now())
     }
 
-    pub fn is_valid_at(&self, now_cet: DateTime<FixedOffset>) -> bool {
-        let saved_cet = self.date.with_timezone(&CET);
+    pub fn is_valid_at(&self, now_frankfurt: DateTime<Frankfurt>) -> bool {
+        let saved_frankfurt = self.date.with_timezone(&Frankfurt);
 
         // Shortcut: if the saved time is somehow *in the future* vs. 'now', treat as invalid.
-        if saved_cet > now_cet {
+        if saved_frankfurt > now_frankfurt {
             return false;
         }
 
         // This can be optimized, but it won't make a difference for the application
-        let hollidays_opt = if now_cet.year() == saved_cet.year() {
-            Some(Rc::new(Hollidays::new(now_cet.year())))
+        let calendar_opt = if now_frankfurt.year() == saved_frankfurt.year() {
+            TargetCalendar::new(now_frankfurt.year()).map(Rc::new)
         } else {
             None
         };
 
-        let mut day_iter = saved_cet.date_naive();
-        let end_day = now_cet.date_naive();
+        let mut day_iter = saved_frankfurt.date_naive();
+        let end_day = now_frankfurt.date_naive();
 
         // Helper: checks if a day is open (ECB publishes).
         // weekend (Sat/Sun) or holiday is "closed".
@@ -47,27 +59,22 @@ impl CacheLine {
             let wd = date.weekday();
             let is_weekend = wd == Weekday::Sat || wd == Weekday::Sun;
 
-            let hollidays = hollidays_opt
+            let calendar = calendar_opt
                 .clone()
-                .unwrap_or_else(|| Rc::new(Hollidays::new(date.year())));
+                .or_else(|| TargetCalendar::new(date.year()).map(Rc::new));
 
-            let is_holiday = hollidays.is_holliday(&date);
+            // No rates are published outside of the calendar
+            let is_closing_day = calendar.is_none_or(|calendar| calendar.is_closing_day(&date));
 
-            !(is_weekend || is_holiday)
+            !(is_weekend || is_closing_day)
         };
 
         while day_iter <= end_day {
             if is_open_day(day_iter) {
-                // Potential publish time is day_iter at 16:00 CET
-                let publish_time_cet = unsafe {
-                    day_iter
-                        .and_hms_opt(16, 0, 0)
-                        .unwrap_unchecked()
-                        .and_local_timezone(CET)
-                        .unwrap()
-                };
-
-                if publish_time_cet > saved_cet && publish_time_cet <= now_cet {
+                // Potential publish time is day_iter at 16:00 in Frankfurt
+                let publish_time = Frankfurt::publication_time(day_iter);
+
+                if publish_time > saved_frankfurt && publish_time <= now_frankfurt {
                     return false;
                 }
             }
@@ -78,18 +85,135 @@ impl CacheLine {
         true
     }
 
-    pub fn new(exchange_rate_results: Vec<ExchangeRateResult>) -> Self {
-        let date = Local::now().to_utc();
-        Self {
-            exchange_rate_results,
-            date,
+    pub fn new(view: View, exchange_rate_results: Vec<ExchangeRateResult>) -> Self {
+        let mut cache_line = Self {
+            date: Local::now().to_utc(),
+            days: BTreeMap::new(),
+            coverage: Vec::new(),
+        };
+        cache_line.merge(view, exchange_rate_results);
+        cache_line
+    }
+
+    /// The last time anything was fetched from the ECB
+    pub fn fetched_at(&self) -> DateTime<Utc> {
+        self.date
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_fetched_at(mut self, date: DateTime<Utc>) -> Self {
+        self.date = date;
+        self
+    }
+
+    pub fn latest_date(&self) -> Option<NaiveDate> {
+        self.days.last_key_value().map(|(date, _)| *date)
+    }
+
+    /// The publications of `view`, newest first, or None if parts of it were never fetched
+    pub fn get(&self, view: &View) -> Option<Vec<ExchangeRateResult>> {
+        Some(self.publications(view)?.rev().collect())
+    }
+
+    /// The publications of `view`, oldest first, produced one at a time rather than collected.
+    /// None if parts of it were never fetched
+    pub fn publications(
+        &self,
+        view: &View,
+    ) -> Option<impl DoubleEndedIterator<Item = ExchangeRateResult> + '_> {
+        let (latest, _) = self.days.last_key_value()?;
+        let coverage = self.coverage.iter().find(|c| &c.to == latest)?;
+        if &coverage.reach < view {
+            return None;
         }
+
+        let from = view
+            .first_date(*latest)
+            .map_or(coverage.from, |first| first.max(coverage.from));
+        let publications = self
+            .days
+            .range(from..)
+            .map(|(date, rates)| ExchangeRateResult::new(date.to_string().into(), rates.clone()));
+
+        Some(publications)
     }
-}
 
-impl PartialEq<Vec<ExchangeRateResult>> for CacheLine {
-    fn eq(&self, other: &Vec<ExchangeRateResult>) -> bool {
-        &self.exchange_rate_results == other
+    /// Which feed to fetch in order to be able to serve `view`.
+    /// Rather than downloading the full history again, an already cached one is brought up to date.
+    pub fn feed_for(&self, view: &View, latest: NaiveDate) -> View {
+        let can_catch_up = self.coverage.iter().any(|c| {
+            c.reach == View::HistDaysAll && View::covering(c.to, latest) <= View::HistDays90
+        });
+
+        if view == &View::HistDaysAll && can_catch_up {
+            View::HistDays90
+        } else {
+            *view
+        }
+    }
+
+    /// Merges all publications of a freshly fetched `view` into the history
+    pub fn merge(&mut self, view: View, exchange_rate_results: Vec<ExchangeRateResult>) {
+        self.date = Local::now().to_utc();
+
+        let mut fetched: Option<(NaiveDate, NaiveDate)> = None;
+        for exchange_rate in exchange_rate_results {
+            let Ok(date) = exchange_rate.time.parse::<NaiveDate>() else {
+                continue;
+            };
+            fetched =
+                Some(fetched.map_or((date, date), |(from, to)| (from.min(date), to.max(date))));
+            self.days.insert(date, exchange_rate.rates);
+        }
+        let Some((from, to)) = fetched else {
+            return;
+        };
+
+        let mut merged = Coverage {
+            from,
+            to,
+            reach: view,
+        };
+        let mut coverage = Vec::with_capacity(self.coverage.len() + 1);
+        for c in self.coverage.drain(..) {
+            let is_before = c.to < merged.from && !Self::is_contiguous(c.to, merged.from);
+            let is_after = merged.to < c.from && !Self::is_contiguous(merged.to, c.from);
+            if is_before || is_after {
+                coverage.push(c);
+                continue;
+            }
+
+            if c.from < merged.from || (c.from == merged.from && c.reach > merged.reach) {
+                merged.reach = c.reach;
+            }
+            merged.from = merged.from.min(c.from);
+            merged.to = merged.to.max(c.to);
+        }
+        coverage.push(merged);
+        coverage.sort_by_key(|c| c.from);
+        self.coverage = coverage;
+    }
+
+    /// Merges publications which were fetched earlier, e.g. by an older version of the cache.
+    /// The history is then only as fresh as the earliest fetch.
+    pub fn merge_fetched_at(
+        &mut self,
+        view: View,
+        exchange_rate_results: Vec<ExchangeRateResult>,
+        fetched_at: DateTime<Utc>,
+    ) {
+        let date = self.date.min(fetched_at);
+        self.merge(view, exchange_rate_results);
+        self.date = date;
+    }
+
+    /// Whether no publication can have happened in between the two dates.
+    fn is_contiguous(before: NaiveDate, after: NaiveDate) -> bool {
+        before
+            .iter_days()
+            .skip(1)
+            .take_while(|date| date < &after)
+            .all(|date| !TargetCalendar::is_publication_day(&date))
     }
 }
 
@@ -101,67 +225,228 @@ mod tests {
     fn cl(date_utc: DateTime<Utc>) -> CacheLine {
         CacheLine {
             date: date_utc,
-            exchange_rate_results: vec![],
+            days: BTreeMap::new(),
+            coverage: vec![],
         }
     }
 
+    fn publications(dates: &[&str]) -> Vec<ExchangeRateResult> {
+        dates
+            .iter()
+            .map(|date| {
+                ExchangeRateResult::new((*date).into(), HashMap::from([("USD".into(), 1.0)]))
+            })
+            .collect()
+    }
+
+    fn times(exchange_rate_results: Option<Vec<ExchangeRateResult>>) -> Option<Vec<SmolStr>> {
+        exchange_rate_results.map(|x| x.into_iter().map(|x| x.time).collect())
+    }
+
     #[test]
     fn test_cache_in_future() {
-        let now_cet = Utc
+        let now_frankfurt = Utc
             .with_ymd_and_hms(2025, 1, 1, 9, 0, 0)
             .unwrap()
-            .with_timezone(&CET);
+            .with_timezone(&Frankfurt);
         let future_utc = Utc.with_ymd_and_hms(2025, 1, 2, 9, 0, 0).unwrap();
-        assert!(!cl(future_utc).is_valid_at(now_cet));
+        assert!(!cl(future_utc).is_valid_at(now_frankfurt));
     }
 
     #[test]
     fn test_same_open_day_before_16() {
-        let now_cet = Utc
+        let now_frankfurt = Utc
             .with_ymd_and_hms(2025, 1, 8, 12, 0, 0)
             .unwrap()
-            .with_timezone(&CET);
+            .with_timezone(&Frankfurt);
         let cache_utc = Utc.with_ymd_and_hms(2025, 1, 8, 10, 0, 0).unwrap();
-        assert!(cl(cache_utc).is_valid_at(now_cet));
+        assert!(cl(cache_utc).is_valid_at(now_frankfurt));
     }
 
     #[test]
     fn test_same_day_after_16() {
-        let now_cet = Utc
+        let now_frankfurt = Utc
             .with_ymd_and_hms(2025, 1, 8, 17, 0, 0)
             .unwrap()
-            .with_timezone(&CET);
+            .with_timezone(&Frankfurt);
         let cache_utc = Utc.with_ymd_and_hms(2025, 1, 8, 14, 0, 0).unwrap();
-        assert!(!cl(cache_utc).is_valid_at(now_cet));
+        assert!(!cl(cache_utc).is_valid_at(now_frankfurt));
     }
 
     #[test]
     fn test_saved_after_16_same_day() {
-        let now_cet = Utc
+        let now_frankfurt = Utc
             .with_ymd_and_hms(2025, 1, 8, 18, 0, 0)
             .unwrap()
-            .with_timezone(&CET);
+            .with_timezone(&Frankfurt);
         let cache_utc = Utc.with_ymd_and_hms(2025, 1, 8, 17, 0, 0).unwrap();
-        assert!(cl(cache_utc).is_valid_at(now_cet));
+        assert!(cl(cache_utc).is_valid_at(now_frankfurt));
     }
 
     #[test]
     fn test_multi_day_old_cache_should_invalidate_if_open_day_passed() {
-        let now_cet = Utc
+        let now_frankfurt = Utc
             .with_ymd_and_hms(2025, 1, 10, 18, 0, 0)
             .unwrap()
-            .with_timezone(&CET);
+            .with_timezone(&Frankfurt);
         let cache_utc = Utc.with_ymd_and_hms(2025, 1, 5, 10, 0, 0).unwrap();
-        assert!(!cl(cache_utc).is_valid_at(now_cet));
+        assert!(!cl(cache_utc).is_valid_at(now_frankfurt));
     }
 
     #[test]
     fn test_multi_day_holiday_scenario() {
-        let now_cet = Utc
+        let now_frankfurt = Utc
+            .with_ymd_and_hms(2025, 12, 26, 19, 0, 0)
+            .unwrap()
+            .with_timezone(&Frankfurt);
+        let cache_utc = Utc.with_ymd_and_hms(2025, 12, 24, 16, 0, 0).unwrap();
+        assert!(cl(cache_utc).is_valid_at(now_frankfurt));
+    }
+
+    #[test]
+    fn test_published_on_staff_holliday() {
+        // The ECB staff is off on christmas eve, but TARGET is open
+        let now_frankfurt = Utc
             .with_ymd_and_hms(2025, 12, 26, 19, 0, 0)
             .unwrap()
-            .with_timezone(&CET);
+            .with_timezone(&Frankfurt);
         let cache_utc = Utc.with_ymd_and_hms(2025, 12, 24, 10, 0, 0).unwrap();
-        assert!(cl(cache_utc).is_valid_at(now_cet));
+        assert!(!cl(cache_utc).is_valid_at(now_frankfurt));
+    }
+
+    #[test]
+    fn test_summer_time_publication() {
+        // 16:00 in Frankfurt is 14:00 UTC during summer time
+        let now_frankfurt = Utc
+            .with_ymd_and_hms(2025, 7, 8, 14, 30, 0)
+            .unwrap()
+            .with_timezone(&Frankfurt);
+        let before_publication = Utc.with_ymd_and_hms(2025, 7, 8, 13, 30, 0).unwrap();
+        let after_publication = Utc.with_ymd_and_hms(2025, 7, 8, 14, 10, 0).unwrap();
+        assert!(!cl(before_publication).is_valid_at(now_frankfurt));
+        assert!(cl(after_publication).is_valid_at(now_frankfurt));
+    }
+
+    #[test]
+    fn test_spring_transition_weekend() {
+        // Summer time starts on sunday 2025-03-30
+        let friday_after_publication = Utc.with_ymd_and_hms(2025, 3, 28, 15, 30, 0).unwrap();
+        let sunday = Utc
+            .with_ymd_and_hms(2025, 3, 30, 20, 0, 0)
+            .unwrap()
+            .with_timezone(&Frankfurt);
+        assert!(cl(friday_after_publication).is_valid_at(sunday));
+
+        let monday_before_publication = Utc
+            .with_ymd_and_hms(2025, 3, 31, 13, 59, 0)
+            .unwrap()
+            .with_timezone(&Frankfurt);
+        let monday_after_publication = Utc
+            .with_ymd_and_hms(2025, 3, 31, 14, 0, 0)
+            .unwrap()
+            .with_timezone(&Frankfurt);
+        assert!(cl(friday_after_publication).is_valid_at(monday_before_publication));
+        assert!(!cl(friday_after_publication).is_valid_at(monday_after_publication));
+    }
+
+    #[test]
+    fn test_autumn_transition_weekend() {
+        // Summer time ends on sunday 2025-10-26
+        let friday_after_publication = Utc.with_ymd_and_hms(2025, 10, 24, 14, 30, 0).unwrap();
+        let monday_before_publication = Utc
+            .with_ymd_and_hms(2025, 10, 27, 14, 30, 0)
+            .unwrap()
+            .with_timezone(&Frankfurt);
+        let monday_after_publication = Utc
+            .with_ymd_and_hms(2025, 10, 27, 15, 0, 0)
+            .unwrap()
+            .with_timezone(&Frankfurt);
+        assert!(cl(friday_after_publication).is_valid_at(monday_before_publication));
+        assert!(!cl(friday_after_publication).is_valid_at(monday_after_publication));
+    }
+
+    #[test]
+    fn test_smaller_views_are_served_from_larger() {
+        let cache_line = CacheLine::new(
+            View::HistDaysAll,
+            publications(&["2025-01-10", "2025-01-09", "1999-01-04"]),
+        );
+        assert_eq!(
+            times(cache_line.get(&View::TODAY)),
+            Some(vec!["2025-01-10".into()])
+        );
+        assert_eq!(
+            times(cache_line.get(&View::HistDays90)),
+            Some(vec!["2025-01-10".into(), "2025-01-09".into()])
+        );
+        assert_eq!(
+            times(cache_line.get(&View::HistDaysAll)),
+            Some(vec![
+                "2025-01-10".into(),
+                "2025-01-09".into(),
+                "1999-01-04".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_larger_views_are_not_served_from_smaller() {
+        let cache_line = CacheLine::new(View::HistDays90, publications(&["2025-01-10"]));
+        assert!(cache_line.get(&View::HistDays90).is_some());
+        assert!(cache_line.get(&View::HistDaysAll).is_none());
+    }
+
+    #[test]
+    fn test_merge_contiguous_keeps_reach() {
+        let mut cache_line = CacheLine::new(
+            View::HistDaysAll,
+            publications(&["2025-01-10", "2025-01-09"]),
+        );
+        // Friday to monday, over a weekend
+        cache_line.merge(View::TODAY, publications(&["2025-01-13"]));
+        assert_eq!(
+            times(cache_line.get(&View::HistDaysAll)),
+            Some(vec![
+                "2025-01-13".into(),
+                "2025-01-10".into(),
+                "2025-01-09".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_contiguous_over_closing_days() {
+        let mut cache_line = CacheLine::new(View::HistDaysAll, publications(&["2025-04-17"]));
+        // Good friday to easter monday
+        cache_line.merge(View::TODAY, publications(&["2025-04-22"]));
+        assert!(cache_line.get(&View::HistDaysAll).is_some());
+    }
+
+    #[test]
+    fn test_merge_with_gap_loses_reach() {
+        let mut cache_line = CacheLine::new(View::HistDaysAll, publications(&["2025-01-08"]));
+        cache_line.merge(View::TODAY, publications(&["2025-01-10"]));
+        assert!(cache_line.get(&View::HistDaysAll).is_none());
+        assert!(cache_line.get(&View::TODAY).is_some());
+
+        let today = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+        assert_eq!(
+            cache_line.feed_for(&View::HistDaysAll, today),
+            View::HistDays90
+        );
+
+        // The 90 days feed bridges the gap
+        cache_line.merge(
+            View::HistDays90,
+            publications(&["2025-01-10", "2025-01-09", "2025-01-08"]),
+        );
+        assert_eq!(
+            times(cache_line.get(&View::HistDaysAll)),
+            Some(vec![
+                "2025-01-10".into(),
+                "2025-01-09".into(),
+                "2025-01-08".into()
+            ])
+        );
     }
 }
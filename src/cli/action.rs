@@ -0,0 +1,35 @@
+use clap::{Args, Subcommand};
+use smol_str::SmolStr;
+
+use super::FormatOption;
+
+#[derive(Debug, Subcommand)]
+pub enum Action {
+    /// Convert an amount from one currency into one or more others
+    Convert(ConvertArgs),
+    /// Summary statistics per currency over the selected days
+    Stats(FormatArgs),
+    /// Correlation of the daily log returns between every pair of currencies
+    Correlate(FormatArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct FormatArgs {
+    /// The output format, which can be given before or after the action
+    #[arg(value_enum)]
+    pub format: Option<FormatOption>,
+}
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    /// The amount to convert
+    pub amount: f64,
+
+    /// The currency to convert from
+    #[arg(value_name = "FROM")]
+    pub source: SmolStr,
+
+    /// The currencies to convert to
+    #[arg(value_name = "TO", required = true)]
+    pub targets: Vec<SmolStr>,
+}
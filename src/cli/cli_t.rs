@@ -1,59 +1,218 @@
+use chrono::NaiveDate;
 use clap::{Parser, ValueEnum};
 use smol_str::SmolStr;
+use std::path::PathBuf;
 
-use super::{ShowDays, SortBy};
+use crate::DateRange;
+use crate::client::CachePolicy;
+
+use super::{Action, Aggregate, FillPolicy, Layout, Period, ShowDays, SortBy};
 
 #[derive(Debug, Parser)]
 #[command(author, version, about)]
 pub struct Cli {
     /// Which currencies do you want to fetch rates for?
-    #[arg(long = "currencies", short = 'c')]
+    #[arg(long = "currencies", short = 'c', global = true)]
     pub currencies: Vec<SmolStr>,
 
     #[arg(value_enum, default_value_t = FormatOption::Plain)]
     pub command: FormatOption,
 
-    /// Don't show time in output
-    #[arg(long = "no-time")]
+    #[command(subcommand)]
+    pub action: Option<Action>,
+
+    /// Don't show time in output. CSV and TSV keep their date column
+    #[arg(long = "no-time", global = true)]
     pub no_time: bool,
 
+    /// Don't print the row of column names in CSV and TSV output
+    #[arg(long = "no-header", global = true)]
+    pub no_header: bool,
+
     /// Print currencies in a compact single line
-    #[arg(long = "compact")]
+    #[arg(long = "compact", global = true)]
     pub compact: bool,
 
     /// Override the cache
-    #[arg(long = "no-cache")]
+    #[arg(long = "no-cache", global = true)]
     pub no_cache: bool,
 
+    /// Never use the network, only read from the cache. Outdated rates exit with code 75
+    #[arg(long = "offline", conflicts_with = "no_cache", global = true)]
+    pub offline: bool,
+
+    /// Write the output to this file, rather than to stdout
+    #[arg(long = "output", short = 'o', value_name = "FILE", global = true)]
+    pub output: Option<PathBuf>,
+
+    /// Rebase every currency in SVG charts to an index, which is 100 at the first date
+    #[arg(long = "rebase", global = true)]
+    pub rebase: bool,
+
+    /// Draw charts with ASCII characters only
+    #[arg(long = "ascii", global = true)]
+    pub ascii: bool,
+
+    /// Skip the Beancount prices which are already in this journal
+    #[arg(long = "skip-existing", value_name = "FILE", global = true)]
+    pub skip_existing: Option<PathBuf>,
+
     /// Force color in output. Normally it will disable color in pipes
-    #[arg(long = "force-color")]
+    #[arg(long = "force-color", global = true)]
     pub force_color: bool,
 
     /// Sort by the currency name (in alphabetical order), or by the rate value (low -> high)
-    #[arg(value_enum, long = "sort-by", default_value_t = SortBy::Currency)]
+    #[arg(value_enum, long = "sort-by", default_value_t = SortBy::Currency, global = true)]
     pub sort_by: SortBy,
 
+    /// How to lay out rates of multiple days
+    #[arg(value_enum, long = "layout", default_value_t = Layout::Long, global = true)]
+    pub layout: Layout,
+
     /// Recalculate to the perspective from an included currency
-    #[arg(long = "perspective", short = 'p')]
+    #[arg(long = "perspective", short = 'p', global = true)]
     pub perspective: Option<SmolStr>,
 
     /// Invert the rate
-    #[arg(long = "invert", short = 'i')]
+    #[arg(long = "invert", short = 'i', global = true)]
     pub should_invert: bool,
 
+    /// Group the rates by period, e.g. for monthly averages or month-end closing rates
+    #[arg(value_enum, long = "resample", global = true)]
+    pub resample: Option<Period>,
+
+    /// Which aggregate to use as the rate of each period. Every aggregate is included in the
+    /// JSON, YAML, TOML and NDJSON output, and in the long CSV and TSV layout.
+    #[arg(value_enum, long = "aggregate", default_value_t = Aggregate::Mean, requires = "resample", global = true)]
+    pub aggregate: Aggregate,
+
+    /// Fill in the days without a publication, e.g. weekends and hollidays
+    #[arg(value_enum, long = "fill", conflicts_with = "resample", global = true)]
+    pub fill: Option<FillPolicy>,
+
+    /// Show the change of every rate since the previous publication
+    #[arg(long = "show-change", global = true)]
+    pub show_change: bool,
+
     /// Max decimals to keep in price.
-    #[arg(long = "max-decimals", short = 'd', default_value_t = 5)]
+    #[arg(long = "max-decimals", short = 'd', default_value_t = 5, global = true)]
     pub max_decimals: u8,
 
     /// Amount of data
-    #[arg(default_value_t = ShowDays::Days(1), long="show-days", short='s')]
+    #[arg(default_value_t = ShowDays::Days(1), long="show-days", short='s', global = true)]
     pub show_days: ShowDays,
+
+    /// Only show rates published on or after this date (YYYY-MM-DD)
+    #[arg(long = "from", conflicts_with = "show_days", global = true)]
+    pub from: Option<NaiveDate>,
+
+    /// Only show rates published on or before this date (YYYY-MM-DD)
+    #[arg(long = "to", conflicts_with = "show_days", global = true)]
+    pub to: Option<NaiveDate>,
+
+    /// Show the rates of a single date (YYYY-MM-DD), or of the last publication before it
+    #[arg(long = "date", conflicts_with_all = ["show_days", "from", "to"], global = true)]
+    pub date: Option<NaiveDate>,
+}
+
+impl Cli {
+    pub fn date_range(&self) -> DateRange {
+        DateRange::new(self.from, self.to)
+    }
+
+    /// Takes the format given after an action, e.g. `stats csv`, over the one before it
+    pub fn with_action_format(mut self) -> Self {
+        if let Some(Action::Stats(args) | Action::Correlate(args)) = self.action.as_ref()
+            && let Some(format) = args.format
+        {
+            self.command = format;
+        }
+        self
+    }
+
+    pub fn cache_policy(&self) -> CachePolicy {
+        if self.offline {
+            CachePolicy::Offline
+        } else if self.no_cache {
+            CachePolicy::NoCache
+        } else {
+            CachePolicy::StaleIfError
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum FormatOption {
     /// JSON output
     Json,
+    /// Newline delimited JSON, streamed one record per line
+    Ndjson,
+    /// YAML, with the same structure as the JSON output
+    Yaml,
+    /// TOML, with the same structure as the JSON output
+    Toml,
     /// Plain line-by-line output (with extra flags)
     Plain,
+    /// Comma separated values
+    Csv,
+    /// Tab separated values
+    Tsv,
+    /// GitHub flavoured markdown tables
+    Markdown,
+    /// Ledger and hledger price directives
+    Ledger,
+    /// Beancount price directives
+    Beancount,
+    /// Sparklines per currency, and a line chart of one or two currencies
+    Chart,
+    /// An SVG image of the rates over time
+    Svg,
+    /// Prometheus gauges, e.g. for the textfile collector of node_exporter
+    Prometheus,
+    /// A standalone HTML report
+    Html,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_options_after_action() {
+        let cli = Cli::try_parse_from(["ecb-rates", "stats", "-p", "SEK", "-c", "USD"]).unwrap();
+        assert_eq!(cli.perspective.as_deref(), Some("SEK"));
+        assert_eq!(cli.currencies, ["USD"]);
+
+        let args = [
+            "ecb-rates",
+            "convert",
+            "10",
+            "EUR",
+            "USD",
+            "-c",
+            "SEK",
+            "-i",
+        ];
+        assert!(Cli::try_parse_from(args).unwrap().should_invert);
+
+        let args = [
+            "ecb-rates",
+            "correlate",
+            "csv",
+            "--layout",
+            "wide",
+            "--sort-by",
+            "rate",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap().with_action_format();
+        assert!(matches!(cli.command, FormatOption::Csv));
+        assert_eq!(cli.layout, Layout::Wide);
+        assert!(matches!(cli.sort_by, SortBy::Rate));
+    }
 }
@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One row per date and currency, or one markdown table per date
+    Long,
+    /// One row per date, with one column per currency
+    Wide,
+    /// One row per currency, with one column per date
+    Matrix,
+}
@@ -1,7 +1,14 @@
+mod action;
 mod cli_t;
+mod layout;
 mod since;
 mod sort_by;
 
+pub use action::{Action, ConvertArgs, FormatArgs};
 pub use cli_t::{Cli, FormatOption};
+pub use layout::Layout;
 pub use since::ShowDays;
 pub use sort_by::SortBy;
+
+// Parsed here, but they belong to the analysis of the rates
+pub use crate::analysis::{Aggregate, FillPolicy, Period};
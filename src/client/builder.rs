@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::DateTime;
+use reqwest::Client;
+
+use super::{CachePolicy, EcbClient, Endpoints};
+use crate::Frankfurt;
+
+#[derive(Debug, Default)]
+pub struct EcbClientBuilder {
+    cache_dir: Option<PathBuf>,
+    cache_policy: CachePolicy,
+    http_client: Option<Client>,
+    timeout: Option<Duration>,
+    endpoints: Endpoints,
+    now: Option<DateTime<Frankfurt>>,
+}
+
+impl EcbClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The directory to keep the cache in. Defaults to the config directory of the OS
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    pub fn cache_policy(mut self, cache_policy: CachePolicy) -> Self {
+        self.cache_policy = cache_policy;
+        self
+    }
+
+    pub fn http_client(mut self, http_client: Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Timeout for every request to the ECB
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Pins the current time, which decides whether the cache is outdated and which feed covers a
+    /// date. Defaults to the clock
+    pub fn now(mut self, now: DateTime<Frankfurt>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    pub fn build(self) -> EcbClient {
+        EcbClient {
+            http_client: self.http_client.unwrap_or_default(),
+            timeout: self.timeout,
+            cache_dir: self.cache_dir,
+            cache_policy: self.cache_policy,
+            endpoints: self.endpoints,
+            now: self.now,
+        }
+    }
+}
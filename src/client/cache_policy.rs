@@ -0,0 +1,13 @@
+/// How [super::EcbClient] uses the cache on disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Use the cache while it's valid, and fall back to outdated rates if the ECB can't be reached
+    #[default]
+    StaleIfError,
+    /// Use the cache while it's valid, but never return outdated rates
+    Revalidate,
+    /// Neither read from, nor write to the cache
+    NoCache,
+    /// Never use the network, only read from the cache, outdated or not
+    Offline,
+}
@@ -0,0 +1,399 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::Client;
+
+use super::{CachePolicy, EcbClientBuilder, Endpoints};
+use crate::caching::Cache;
+use crate::models::ExchangeRateResult;
+use crate::parsing::parse;
+use crate::utils_calc::{filter_date_range, rate_on_or_before};
+use crate::{DateRange, Error, Frankfurt, TargetCalendar, View};
+
+/// Fetches rates from the ECB, caching them on disk according to its [CachePolicy]
+#[derive(Debug)]
+pub struct EcbClient {
+    pub(super) http_client: Client,
+    pub(super) timeout: Option<Duration>,
+    pub(super) cache_dir: Option<PathBuf>,
+    pub(super) cache_policy: CachePolicy,
+    pub(super) endpoints: Endpoints,
+    pub(super) now: Option<DateTime<Frankfurt>>,
+}
+
+#[derive(Debug)]
+pub enum Freshness {
+    Fresh,
+    /// The ECB couldn't be used, so the rates are from an outdated cache
+    Stale {
+        /// The last time the cache was fetched from the ECB
+        fetched_at: DateTime<Utc>,
+        /// The date of the latest cached publication
+        latest: Option<NaiveDate>,
+        /// Why the ECB couldn't be used, None when offline
+        error: Option<Error>,
+    },
+}
+
+/// Where the publications of a view were found
+enum Source {
+    Cache(Cache),
+    /// Newest first, since the cache couldn't serve the view
+    Parsed(Vec<ExchangeRateResult>),
+}
+
+impl Source {
+    /// The publications of the view, oldest first
+    fn publications(&self, view: &View) -> impl Iterator<Item = ExchangeRateResult> + '_ {
+        let cached = match self {
+            Self::Cache(cache) => cache
+                .get_cache_line()
+                .and_then(|cache_line| cache_line.publications(view)),
+            Self::Parsed(_) => None,
+        };
+        let parsed = match self {
+            Self::Parsed(parsed) => Some(parsed.iter().rev().cloned()),
+            Self::Cache(_) => None,
+        };
+        cached
+            .into_iter()
+            .flatten()
+            .chain(parsed.into_iter().flatten())
+    }
+}
+
+#[derive(Debug)]
+pub struct Fetched {
+    /// Ordered from the newest to the oldest publication
+    pub exchange_rate_results: Vec<ExchangeRateResult>,
+    pub freshness: Freshness,
+}
+
+impl Default for EcbClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EcbClient {
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> EcbClientBuilder {
+        EcbClientBuilder::new()
+    }
+
+    /// The latest publication
+    pub async fn latest(&self) -> crate::Result<ExchangeRateResult> {
+        let fetched = self.fetch(&View::TODAY).await?;
+        fetched
+            .exchange_rate_results
+            .into_iter()
+            .max_by(|a, b| a.time.cmp(&b.time))
+            .ok_or(Error::NoRates)
+    }
+
+    /// Every publication in the range, newest first
+    pub async fn history(&self, range: &DateRange) -> crate::Result<Vec<ExchangeRateResult>> {
+        let view = range.to_view(self.latest_publication_day());
+        let mut exchange_rate_results = self.fetch(&view).await?.exchange_rate_results;
+        filter_date_range(&mut exchange_rate_results, range);
+        Ok(exchange_rate_results)
+    }
+
+    /// The publication on the date, or the last one before it if nothing was published that day
+    pub async fn day(&self, date: NaiveDate) -> crate::Result<ExchangeRateResult> {
+        let view = Self::view_for_date_at(date, self.now())?;
+        let fetched = self.fetch(&view).await?;
+        let (_, exchange_rate) = rate_on_or_before(&fetched.exchange_rate_results, &date)
+            .ok_or(Error::NoPublication(date))?;
+        Ok(exchange_rate.clone())
+    }
+
+    /// The rate of a single currency on the date, or on the last publication before it
+    pub async fn rate_on(
+        &self,
+        date: NaiveDate,
+        currency: &str,
+    ) -> crate::Result<ExchangeRateResult> {
+        let mut exchange_rate = self.day(date).await?;
+        exchange_rate.rates.retain(|key, _| key == currency);
+        if exchange_rate.rates.is_empty() {
+            return Err(Error::UnknownCurrency {
+                currency: currency.into(),
+                missing_dates: vec![exchange_rate.time],
+            });
+        }
+        Ok(exchange_rate)
+    }
+
+    /// The smallest view that contains the publication on, or before, the date.
+    /// Fails with [Error::NoPublication] for dates before the first publication.
+    pub fn view_for_date(date: NaiveDate) -> crate::Result<View> {
+        Self::view_for_date_at(date, Frankfurt::now())
+    }
+
+    fn view_for_date_at(date: NaiveDate, now: DateTime<Frankfurt>) -> crate::Result<View> {
+        let publication_day =
+            TargetCalendar::last_publication_day(date).ok_or(Error::NoPublication(date))?;
+        let latest = TargetCalendar::latest_publication_day(now);
+        Ok(View::covering(publication_day, latest))
+    }
+
+    /// The pinned time of the builder, or else the clock
+    fn now(&self) -> DateTime<Frankfurt> {
+        self.now.unwrap_or_else(Frankfurt::now)
+    }
+
+    fn latest_publication_day(&self) -> NaiveDate {
+        TargetCalendar::latest_publication_day(self.now())
+    }
+
+    /// Every publication in the view, from the cache or the ECB depending on the [CachePolicy]
+    pub async fn fetch(&self, view: &View) -> crate::Result<Fetched> {
+        let (source, freshness) = self.load(view).await?;
+        let mut exchange_rate_results = source.publications(view).collect::<Vec<_>>();
+        exchange_rate_results.reverse();
+        Ok(Fetched {
+            exchange_rate_results,
+            freshness,
+        })
+    }
+
+    /// Every publication in the view, like [Self::fetch], but handed to `f` oldest first and one
+    /// at a time. Served from the cache, the publications are never all held in memory at once.
+    pub async fn for_each<E: From<Error>>(
+        &self,
+        view: &View,
+        mut f: impl FnMut(ExchangeRateResult) -> Result<(), E>,
+    ) -> Result<Freshness, E> {
+        let (source, freshness) = self.load(view).await?;
+        for exchange_rate in source.publications(view) {
+            f(exchange_rate)?;
+        }
+        Ok(freshness)
+    }
+
+    async fn load(&self, view: &View) -> crate::Result<(Source, Freshness)> {
+        let cache = match self.cache_policy {
+            CachePolicy::NoCache => None,
+            _ => match self.cache_dir.as_ref() {
+                Some(cache_dir) => Cache::load_from(cache_dir.clone()),
+                None => Cache::load(),
+            },
+        };
+        let Some(mut cache) = cache else {
+            if self.cache_policy == CachePolicy::Offline {
+                return Err(Error::NotCached(*view));
+            }
+            let parsed = self.get_and_parse(view).await?;
+            return Ok((Source::Parsed(parsed), Freshness::Fresh));
+        };
+
+        if let Some(cache_line) = cache.get_cache_line()
+            && cache_line.publications(view).is_some()
+        {
+            if cache_line.is_valid_at(self.now()) {
+                return Ok((Source::Cache(cache), Freshness::Fresh));
+            }
+            if self.cache_policy == CachePolicy::Offline {
+                let freshness = Freshness::Stale {
+                    fetched_at: cache_line.fetched_at(),
+                    latest: cache_line.latest_date(),
+                    error: None,
+                };
+                return Ok((Source::Cache(cache), freshness));
+            }
+        }
+        if self.cache_policy == CachePolicy::Offline {
+            return Err(Error::NotCached(*view));
+        }
+
+        match self.fetch_into_cache(&mut cache, view).await {
+            Ok(parsed) => {
+                cache.save()?;
+                let source = parsed.map_or(Source::Cache(cache), Source::Parsed);
+                Ok((source, Freshness::Fresh))
+            }
+            Err(e) => {
+                let stale = cache
+                    .get_cache_line()
+                    .filter(|_| self.cache_policy == CachePolicy::StaleIfError)
+                    .filter(|cache_line| cache_line.publications(view).is_some());
+                let Some(cache_line) = stale else {
+                    return Err(e);
+                };
+                let freshness = Freshness::Stale {
+                    fetched_at: cache_line.fetched_at(),
+                    latest: cache_line.latest_date(),
+                    error: Some(e),
+                };
+                Ok((Source::Cache(cache), freshness))
+            }
+        }
+    }
+
+    /// Brings the cache up to date. Returns the freshly parsed view, if the cache still can't
+    /// serve it.
+    async fn fetch_into_cache(
+        &self,
+        cache: &mut Cache,
+        view: &View,
+    ) -> crate::Result<Option<Vec<ExchangeRateResult>>> {
+        let feed = cache.get_cache_line().map_or(*view, |cache_line| {
+            cache_line.feed_for(view, self.latest_publication_day())
+        });
+        let parsed = self.get_and_parse(&feed).await?;
+        cache.merge(feed, parsed);
+
+        let is_cached = cache
+            .get_cache_line()
+            .is_some_and(|cache_line| cache_line.publications(view).is_some());
+        if is_cached {
+            return Ok(None);
+        }
+        let parsed = self.get_and_parse(view).await?;
+        cache.merge(*view, parsed.clone());
+        Ok(Some(parsed))
+    }
+
+    async fn get_and_parse(&self, view: &View) -> crate::Result<Vec<ExchangeRateResult>> {
+        let mut request = self.http_client.get(self.endpoints.get(view));
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+
+        let xml_content = request.send().await?.error_for_status()?.text().await?;
+        let parsed = parse(&xml_content)?;
+        if parsed.is_empty() {
+            return Err(Error::NoRates);
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caching::CacheLine;
+    use crate::test_utils::TestDir;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    /// Friday 2025-01-10 at the given hour in Frankfurt
+    fn friday_at(hour: u32) -> DateTime<Frankfurt> {
+        Frankfurt.with_ymd_and_hms(2025, 1, 10, hour, 0, 0).unwrap()
+    }
+
+    /// A client with the publications until friday, fetched at 17:00 that friday
+    fn offline_client(cache_dir: &Path, now: DateTime<Frankfurt>) -> EcbClient {
+        let exchange_rate_results = ["2025-01-10", "2025-01-09", "2025-01-08"]
+            .iter()
+            .map(|date| {
+                ExchangeRateResult::new(
+                    (*date).into(),
+                    HashMap::from([("USD".into(), 1.0), ("SEK".into(), 11.0)]),
+                )
+            })
+            .collect();
+        let cache_line = CacheLine::new(View::HistDaysAll, exchange_rate_results)
+            .with_fetched_at(friday_at(17).to_utc());
+        let mut cache = Cache::load_from(cache_dir.to_path_buf()).unwrap();
+        cache.set_cache_line(cache_line);
+        cache.save().unwrap();
+
+        EcbClient::builder()
+            .cache_dir(cache_dir)
+            .cache_policy(CachePolicy::Offline)
+            .now(now)
+            .build()
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_history_from_cache() {
+        let dir = TestDir::new("history");
+        let client = offline_client(&dir.0, friday_at(18));
+        let range = DateRange::new(NaiveDate::from_ymd_opt(2025, 1, 9), None);
+        let history = block_on(client.history(&range)).unwrap();
+        let times = history.iter().map(|x| x.time.as_str()).collect::<Vec<_>>();
+        assert_eq!(times, ["2025-01-10", "2025-01-09"]);
+    }
+
+    #[test]
+    fn test_for_each_oldest_first() {
+        let dir = TestDir::new("for-each");
+        let client = offline_client(&dir.0, friday_at(18));
+        let mut times = Vec::new();
+        let freshness = block_on(client.for_each(&View::HistDays90, |exchange_rate| {
+            times.push(exchange_rate.time);
+            crate::Result::Ok(())
+        }));
+        assert!(matches!(freshness, Ok(Freshness::Fresh)));
+        let fetched = block_on(client.fetch(&View::HistDays90)).unwrap();
+        let mut newest_first = fetched
+            .exchange_rate_results
+            .into_iter()
+            .map(|x| x.time)
+            .collect::<Vec<_>>();
+        newest_first.reverse();
+        assert_eq!(times, newest_first);
+        assert!(times.len() > 1);
+    }
+
+    #[test]
+    fn test_freshness() {
+        let dir = TestDir::new("freshness");
+        let saturday = Frankfurt.with_ymd_and_hms(2025, 1, 11, 12, 0, 0).unwrap();
+        let fetched = block_on(offline_client(&dir.0, saturday).fetch(&View::HistDaysAll));
+        assert!(matches!(fetched.unwrap().freshness, Freshness::Fresh));
+
+        // Outdated by the publication on monday
+        let monday = Frankfurt.with_ymd_and_hms(2025, 1, 13, 16, 0, 0).unwrap();
+        let fetched = block_on(offline_client(&dir.0, monday).fetch(&View::HistDaysAll));
+        assert!(matches!(
+            fetched.unwrap().freshness,
+            Freshness::Stale { .. }
+        ));
+    }
+
+    #[test]
+    fn test_rate_on_falls_back() {
+        let dir = TestDir::new("rate-on");
+        let client = offline_client(&dir.0, friday_at(18));
+        let saturday = NaiveDate::from_ymd_opt(2025, 1, 11).unwrap();
+        let exchange_rate = block_on(client.rate_on(saturday, "USD")).unwrap();
+        assert_eq!(exchange_rate.time, "2025-01-10");
+        assert_eq!(exchange_rate.rates, HashMap::from([("USD".into(), 1.0)]));
+
+        assert!(block_on(client.rate_on(saturday, "XXX")).is_err());
+    }
+
+    #[test]
+    fn test_day_before_first_publication() {
+        let dir = TestDir::new("before-first");
+        let client = offline_client(&dir.0, friday_at(18));
+        let date = NaiveDate::from_ymd_opt(1500, 1, 1).unwrap();
+        assert!(matches!(
+            EcbClient::view_for_date(date),
+            Err(Error::NoPublication(x)) if x == date
+        ));
+        assert!(matches!(
+            block_on(client.day(date)),
+            Err(Error::NoPublication(_))
+        ));
+        assert!(matches!(
+            block_on(client.rate_on(date, "USD")),
+            Err(Error::NoPublication(_))
+        ));
+    }
+}
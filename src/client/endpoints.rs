@@ -0,0 +1,29 @@
+use crate::{View, ecb_url};
+
+/// The urls of the feeds published by the ECB
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    pub today: String,
+    pub hist_days_90: String,
+    pub hist_days_all: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            today: ecb_url::TODAY.to_string(),
+            hist_days_90: ecb_url::hist::DAYS_90.to_string(),
+            hist_days_all: ecb_url::hist::DAYS_ALL.to_string(),
+        }
+    }
+}
+
+impl Endpoints {
+    pub fn get(&self, view: &View) -> &str {
+        match view {
+            View::TODAY => &self.today,
+            View::HistDays90 => &self.hist_days_90,
+            View::HistDaysAll => &self.hist_days_all,
+        }
+    }
+}
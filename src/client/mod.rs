@@ -0,0 +1,9 @@
+mod builder;
+mod cache_policy;
+mod ecb_client;
+mod endpoints;
+
+pub use builder::EcbClientBuilder;
+pub use cache_policy::CachePolicy;
+pub use ecb_client::{EcbClient, Fetched, Freshness};
+pub use endpoints::Endpoints;
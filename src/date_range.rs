@@ -0,0 +1,77 @@
+use chrono::NaiveDate;
+
+use crate::View;
+
+/// An inclusive range of dates, where each end can be left open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    pub fn new(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        Self { from, to }
+    }
+
+    /// True when neither end is set, e.g. the range contains every date
+    pub fn is_unbounded(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    pub fn contains(&self, date: &NaiveDate) -> bool {
+        self.from.is_none_or(|from| &from <= date) && self.to.is_none_or(|to| date <= &to)
+    }
+
+    /// The smallest view containing the range, when the latest publication was on `latest`
+    pub fn to_view(&self, latest: NaiveDate) -> View {
+        match self.from {
+            Some(from) => View::covering(from, latest),
+            None => View::HistDaysAll,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_contains_is_inclusive() {
+        let range = DateRange::new(Some(date(2023, 1, 1)), Some(date(2023, 3, 31)));
+        assert!(range.contains(&date(2023, 1, 1)));
+        assert!(range.contains(&date(2023, 3, 31)));
+        assert!(!range.contains(&date(2022, 12, 31)));
+        assert!(!range.contains(&date(2023, 4, 1)));
+    }
+
+    #[test]
+    fn test_open_ends() {
+        let range = DateRange::new(None, Some(date(2023, 3, 31)));
+        assert!(range.contains(&date(1999, 1, 4)));
+        assert!(!range.contains(&date(2023, 4, 1)));
+
+        let range = DateRange::new(Some(date(2023, 1, 1)), None);
+        assert!(range.contains(&date(2025, 6, 1)));
+        assert!(!range.contains(&date(2022, 12, 31)));
+    }
+
+    #[test]
+    fn test_smallest_view() {
+        let latest = date(2025, 6, 2);
+        let view = |from| DateRange::new(Some(from), None).to_view(latest);
+
+        assert!(matches!(view(date(2025, 6, 2)), View::TODAY));
+        assert!(matches!(view(date(2025, 5, 1)), View::HistDays90));
+        assert!(matches!(view(date(2025, 3, 5)), View::HistDays90));
+        assert!(matches!(view(date(2025, 3, 4)), View::HistDaysAll));
+        assert!(matches!(
+            DateRange::new(None, Some(latest)).to_view(latest),
+            View::HistDaysAll
+        ));
+    }
+}
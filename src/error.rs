@@ -0,0 +1,115 @@
+use std::{fmt, io};
+
+use chrono::NaiveDate;
+use quick_xml::events::attributes::AttrError;
+use reqwest::StatusCode;
+use smol_str::SmolStr;
+
+use crate::View;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The ECB couldn't be reached
+    Network(reqwest::Error),
+    /// The ECB responded, but not successfully
+    HttpStatus(StatusCode),
+    /// The response from the ECB isn't valid XML
+    Xml(quick_xml::Error),
+    /// A rate in the response from the ECB isn't a number
+    InvalidRate { currency: SmolStr, rate: SmolStr },
+    /// The response from the ECB didn't contain any rates
+    NoRates,
+    /// Nothing was published on, or before, the date
+    NoPublication(NaiveDate),
+    /// The currency is missing from the rates on the listed dates
+    UnknownCurrency {
+        currency: SmolStr,
+        missing_dates: Vec<SmolStr>,
+    },
+    /// The cache couldn't be read from, or written to
+    CacheIo(io::Error),
+    /// The cache isn't valid JSON, or doesn't have the expected structure
+    CacheFormat(serde_json::Error),
+    /// The view isn't cached, and the cache policy doesn't allow fetching it
+    NotCached(View),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(e) => write!(f, "Failed to get data from ECB: {}", e),
+            Self::HttpStatus(status) => write!(f, "The ECB responded with {}", status),
+            Self::Xml(e) => write!(f, "Failed to parse data from ECB: {}", e),
+            Self::InvalidRate { currency, rate } => {
+                write!(f, "The rate of {} isn't a number: '{}'", currency, rate)
+            }
+            Self::NoRates => write!(f, "The response from the ECB didn't contain any rates"),
+            Self::NoPublication(date) => {
+                write!(f, "No rates were published on or before {}", date)
+            }
+            Self::UnknownCurrency {
+                currency,
+                missing_dates,
+            } => write!(
+                f,
+                "{} wasn't in the data from the ECB on {}",
+                currency,
+                missing_dates.join(", ")
+            ),
+            Self::CacheIo(e) => write!(f, "Failed to use the cache: {}", e),
+            Self::CacheFormat(e) => write!(f, "The cache is invalid: {}", e),
+            Self::NotCached(view) => write!(
+                f,
+                "The {} rates aren't cached, and can't be fetched in offline mode",
+                view.get_name()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Network(e) => Some(e),
+            Self::Xml(e) => Some(e),
+            Self::CacheIo(e) => Some(e),
+            Self::CacheFormat(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        match e.status() {
+            Some(status) => Self::HttpStatus(status),
+            None => Self::Network(e),
+        }
+    }
+}
+
+impl From<quick_xml::Error> for Error {
+    fn from(e: quick_xml::Error) -> Self {
+        Self::Xml(e)
+    }
+}
+
+impl From<AttrError> for Error {
+    fn from(e: AttrError) -> Self {
+        Self::Xml(e.into())
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::CacheIo(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::CacheFormat(e)
+    }
+}
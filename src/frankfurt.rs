@@ -0,0 +1,160 @@
+use chrono::{
+    DateTime, Datelike, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta,
+    TimeZone, Utc,
+};
+
+const CET: FixedOffset = unsafe { FixedOffset::east_opt(3600).unwrap_unchecked() };
+const CEST: FixedOffset = unsafe { FixedOffset::east_opt(2 * 3600).unwrap_unchecked() };
+
+/// The local time in Frankfurt am Main (Europe/Berlin), where the ECB publishes its rates.
+/// Summer time follows the rules of the EU, which have been in place since 1981.
+/// It starts on the last sunday of March, and ends on the last sunday of October
+/// (September until 1995), both at 01:00 UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frankfurt;
+
+impl Frankfurt {
+    pub fn now() -> DateTime<Self> {
+        Utc::now().with_timezone(&Self)
+    }
+
+    /// The current date in Frankfurt
+    pub fn today() -> NaiveDate {
+        Self::now().date_naive()
+    }
+
+    /// The time at which the ECB publishes rates on the given date, 16:00 in Frankfurt
+    pub fn publication_time(date: NaiveDate) -> DateTime<Self> {
+        let publication_time = unsafe { NaiveTime::from_hms_opt(16, 0, 0).unwrap_unchecked() };
+        // 16:00 is never skipped nor repeated by summer time
+        date.and_time(publication_time)
+            .and_local_timezone(Self)
+            .earliest()
+            .expect("16:00 always exists in Frankfurt")
+    }
+
+    /// The span of summer time in a year, in UTC
+    fn summer_time(year: i32) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        let end_month = match year {
+            ..1981 => return None,
+            1981..=1995 => 9,
+            1996.. => 10,
+        };
+        let start = Self::last_sunday(year, 3);
+        let end = Self::last_sunday(year, end_month);
+        let one_o_clock = NaiveTime::from_hms_opt(1, 0, 0)?;
+
+        Some((start.and_time(one_o_clock), end.and_time(one_o_clock)))
+    }
+
+    fn last_sunday(year: i32, month: u32) -> NaiveDate {
+        let last_day = match month {
+            9 => 30,
+            _ => 31,
+        };
+        let last_day = unsafe { NaiveDate::from_ymd_opt(year, month, last_day).unwrap_unchecked() };
+        last_day - TimeDelta::days(last_day.weekday().num_days_from_sunday() as i64)
+    }
+}
+
+impl TimeZone for Frankfurt {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &Self::Offset) -> Self {
+        Self
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<Self::Offset> {
+        self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<Self::Offset> {
+        // A local time is valid for an offset, if the offset is in use at the resulting instant
+        let is_valid = |offset: FixedOffset| {
+            let utc = *local - TimeDelta::seconds(offset.local_minus_utc() as i64);
+            self.offset_from_utc_datetime(&utc) == offset
+        };
+
+        match (is_valid(CEST), is_valid(CET)) {
+            (true, true) => LocalResult::Ambiguous(CEST, CET),
+            (true, false) => LocalResult::Single(CEST),
+            (false, true) => LocalResult::Single(CET),
+            (false, false) => LocalResult::None,
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> Self::Offset {
+        self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> Self::Offset {
+        match Self::summer_time(utc.year()) {
+            Some((start, end)) if (start..end).contains(utc) => CEST,
+            _ => CET,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset_hours(utc: DateTime<Utc>) -> i32 {
+        utc.with_timezone(&Frankfurt).offset().local_minus_utc() / 3600
+    }
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_spring_transition() {
+        let before = Utc.with_ymd_and_hms(2025, 3, 30, 0, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2025, 3, 30, 1, 0, 0).unwrap();
+        assert_eq!(offset_hours(before), 1);
+        assert_eq!(offset_hours(after), 2);
+
+        // 02:30 is skipped when the clocks jump from 02:00 to 03:00
+        assert_eq!(
+            Frankfurt.from_local_datetime(&local(2025, 3, 30, 2, 30)),
+            LocalResult::None
+        );
+    }
+
+    #[test]
+    fn test_autumn_transition() {
+        let before = Utc.with_ymd_and_hms(2025, 10, 26, 0, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2025, 10, 26, 1, 0, 0).unwrap();
+        assert_eq!(offset_hours(before), 2);
+        assert_eq!(offset_hours(after), 1);
+
+        // 02:30 happens twice when the clocks fall back from 03:00 to 02:00
+        let repeated = Frankfurt.from_local_datetime(&local(2025, 10, 26, 2, 30));
+        assert!(matches!(repeated, LocalResult::Ambiguous(_, _)));
+    }
+
+    #[test]
+    fn test_summer_time_ended_in_september_before_1996() {
+        let october = Utc.with_ymd_and_hms(1995, 10, 10, 12, 0, 0).unwrap();
+        assert_eq!(offset_hours(october), 1);
+        let october = Utc.with_ymd_and_hms(1996, 10, 10, 12, 0, 0).unwrap();
+        assert_eq!(offset_hours(october), 2);
+    }
+
+    #[test]
+    fn test_publication_time() {
+        let winter = NaiveDate::from_ymd_opt(2025, 1, 8).unwrap();
+        let summer = NaiveDate::from_ymd_opt(2025, 7, 8).unwrap();
+        assert_eq!(
+            Frankfurt::publication_time(winter),
+            Utc.with_ymd_and_hms(2025, 1, 8, 15, 0, 0).unwrap()
+        );
+        assert_eq!(
+            Frankfurt::publication_time(summer),
+            Utc.with_ymd_and_hms(2025, 7, 8, 14, 0, 0).unwrap()
+        );
+    }
+}
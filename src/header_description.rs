@@ -5,6 +5,7 @@ use crate::DEFAULT_WIDTH;
 
 pub struct HeaderDescription<'a> {
     header_description: [&'a str; 2],
+    inverted: bool,
 }
 
 impl<'a> Default for HeaderDescription<'a> {
@@ -17,15 +18,35 @@ impl<'a> HeaderDescription<'a> {
     pub fn new() -> Self {
         Self {
             header_description: ["EUR", /*"\u{2217}"*/ "ALL"], // Unicode is ∗
+            inverted: false,
         }
     }
 
     pub fn invert(&mut self) {
         self.header_description.swap(0, 1);
+        self.inverted = !self.inverted;
     }
 
     pub fn replace_eur(&mut self, currency: &'a str) {
-        self.header_description[0] = currency;
+        let base = if self.inverted { 1 } else { 0 };
+        self.header_description[base] = currency;
+    }
+
+    /// The currency the rates are quoted against, e.g. `EUR` or the perspective
+    pub fn base(&self) -> &'a str {
+        self.header_description[if self.inverted { 1 } else { 0 }]
+    }
+
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// The header as plain text, e.g. `EUR to ALL`
+    pub fn plain(&self) -> String {
+        format!(
+            "{} to {}",
+            self.header_description[0], self.header_description[1]
+        )
     }
 }
 
@@ -2,14 +2,23 @@ use chrono::{Days, NaiveDate};
 
 /// Calculates the hollidays recognized by the EU
 /// ECB recognizes the following hollidays https://www.ecb.europa.eu/ecb/contacts/working-hours/html/index.en.html
+/// These are the hollidays of the ECB staff. Rates are published according to [crate::TargetCalendar]
 #[derive(Debug, Clone)]
 pub struct Hollidays {
-    hollidays: [NaiveDate; 15],
+    hollidays: [(NaiveDate, &'static str); 15],
 }
 
 impl Hollidays {
     pub fn is_holliday(&self, date: &NaiveDate) -> bool {
-        self.hollidays.contains(date)
+        self.name(date).is_some()
+    }
+
+    /// The name of the holliday on the given date, e.g. `Good Friday`
+    pub fn name(&self, date: &NaiveDate) -> Option<&'static str> {
+        self.hollidays
+            .iter()
+            .find(|(holliday, _)| holliday == date)
+            .map(|(_, name)| *name)
     }
 
     pub fn new(year: i32) -> Self {
@@ -33,21 +42,24 @@ impl Hollidays {
         let new_years_eve = unsafe { NaiveDate::from_ymd_opt(year, 12, 31).unwrap_unchecked() };
 
         let hollidays = [
-            easter_sunday,
-            easter_monday,
-            good_friday,
-            ascension_day,
-            whit_monday,
-            corpus_christi,
-            year_years_day,
-            labour_day,
-            robert_schuman_declaration,
-            german_unity_day,
-            all_saints_day,
-            christmas_eve,
-            christmas_day,
-            christmas_holiday,
-            new_years_eve,
+            (easter_sunday, "Easter Sunday"),
+            (easter_monday, "Easter Monday"),
+            (good_friday, "Good Friday"),
+            (ascension_day, "Ascension Day"),
+            (whit_monday, "Whit Monday"),
+            (corpus_christi, "Corpus Christi"),
+            (year_years_day, "New Year's Day"),
+            (labour_day, "Labour Day"),
+            (
+                robert_schuman_declaration,
+                "Anniversary of the Schuman Declaration",
+            ),
+            (german_unity_day, "Day of German Unity"),
+            (all_saints_day, "All Saints' Day"),
+            (christmas_eve, "Christmas Eve"),
+            (christmas_day, "Christmas Day"),
+            (christmas_holiday, "Christmas Holiday"),
+            (new_years_eve, "New Year's Eve"),
         ];
         Self { hollidays }
     }
@@ -55,7 +67,7 @@ impl Hollidays {
     /// Returns Easter Sunday for a given year (Gregorian calendar).
     /// This uses a variation of the Butcher's algorithm.
     /// Valid for years 1583..=4099 in the Gregorian calendar.
-    fn calc_easter_sunday(year: i32) -> NaiveDate {
+    pub(crate) fn calc_easter_sunday(year: i32) -> NaiveDate {
         // For reference: https://en.wikipedia.org/wiki/Computus#Butcher's_algorithm
         let a = year % 19;
         let b = year / 100;
@@ -99,6 +111,8 @@ mod tests {
         let labour_day_2025 = NaiveDate::from_ymd_opt(2025, 5, 1).unwrap();
         assert!(holliday.is_holliday(&labour_day_2025), "Labour Day 2025");
 
+        assert_eq!(holliday.name(&labour_day_2025), Some("Labour Day"));
+
         let random_workday_2025 = NaiveDate::from_ymd_opt(2025, 2, 10).unwrap();
         assert!(
             !holliday.is_holliday(&random_workday_2025),
@@ -1,16 +1,29 @@
+pub mod analysis;
 pub mod caching;
 pub mod cli;
+pub mod client;
+mod date_range;
+mod error;
+mod frankfurt;
 mod header_description;
 mod holiday;
 pub mod models;
 pub mod os;
+pub mod output;
 pub mod parsing;
 pub mod table;
+mod target_calendar;
+#[cfg(test)]
+mod test_utils;
 pub mod utils_calc;
 mod view;
 
+pub use date_range::DateRange;
+pub use error::{Error, Result};
+pub use frankfurt::Frankfurt;
 pub use header_description::HeaderDescription;
 pub use holiday::Hollidays;
+pub use target_calendar::TargetCalendar;
 pub use view::View;
 
 const APP_NAME: &str = "ECB-rates";
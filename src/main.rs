@@ -1,25 +1,30 @@
-use anyhow::Context;
-use clap::Parser as _;
-use ecb_rates::caching::{Cache, CacheLine};
-use ecb_rates::HeaderDescription;
-use reqwest::{Client, IntoUrl};
-use smol_str::StrExt;
+use anyhow::{Context, bail};
+use chrono::{Local, NaiveDate};
+use clap::{Parser as _, ValueEnum};
+use ecb_rates::client::{EcbClient, Fetched, Freshness};
+use ecb_rates::{DateRange, Error, Frankfurt, HeaderDescription, TargetCalendar, View};
+use smol_str::{SmolStr, StrExt};
+use std::fs;
+use std::io::{self, Write};
 use std::process::ExitCode;
 
-use ecb_rates::cli::{Cli, FormatOption};
+use ecb_rates::analysis::{correlate, fill_gaps, neighbours, resample, stats};
+use ecb_rates::cli::{Action, Cli, ConvertArgs, FormatOption, Layout};
 use ecb_rates::models::ExchangeRateResult;
-use ecb_rates::parsing::parse;
-use ecb_rates::table::{TableRef, TableTrait as _};
-use ecb_rates::utils_calc::{change_perspective, filter_currencies, invert_rates, round};
+use ecb_rates::output::{
+    Beancount, Chart, Delimited, Html, Markdown, Prometheus, SvgChart, json, ledger, ndjson, plain,
+    toml, yaml,
+};
+use ecb_rates::utils_calc::{
+    change_perspective, convert, filter_currencies, filter_date_range, invert_rates,
+    rate_on_or_before, round, round_conversion, round_correlations, round_stats, with_changes,
+};
 
-async fn get_and_parse(url: impl IntoUrl) -> anyhow::Result<Vec<ExchangeRateResult>> {
-    let client = Client::new();
-    let xml_content = client.get(url).send().await?.text().await?;
-    parse(&xml_content)
-}
+/// Exit code used when the rates were served from an outdated cache
+const EXIT_STALE: u8 = 75;
 
 fn main() -> ExitCode {
-    let cli = Cli::parse();
+    let cli = Cli::parse().with_action_format();
 
     let runtime = match tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -33,7 +38,8 @@ fn main() -> ExitCode {
     };
 
     match runtime.block_on(async_main(cli)) {
-        Ok(_) => ExitCode::SUCCESS,
+        Ok(Freshness::Fresh) => ExitCode::SUCCESS,
+        Ok(Freshness::Stale { .. }) => ExitCode::from(EXIT_STALE),
         Err(e) => {
             eprintln!("Fatal: {:?}", e);
             ExitCode::FAILURE
@@ -41,132 +47,496 @@ fn main() -> ExitCode {
     }
 }
 
-async fn async_main(mut cli: Cli) -> anyhow::Result<()> {
+async fn async_main(mut cli: Cli) -> anyhow::Result<Freshness> {
     if cli.force_color {
         colored::control::set_override(true);
+    } else if cli.output.is_some() {
+        colored::control::set_override(false);
+    }
+
+    let client = EcbClient::builder()
+        .cache_policy(cli.cache_policy())
+        .build();
+
+    if let Some(Action::Convert(args)) = cli.action.as_ref() {
+        return convert_main(&cli, &client, args).await;
+    }
+
+    if cli.show_change && cli.action.is_none() && !shows_changes(&cli) {
+        let mut format = name(cli.command);
+        if matches!(
+            cli.command,
+            FormatOption::Plain | FormatOption::Csv | FormatOption::Tsv | FormatOption::Markdown
+        ) {
+            format.push_str(&format!(" in the {} layout", name(cli.layout)));
+        }
+        bail!(
+            "Changes can't be shown as {}, use the long layout of plain, csv, tsv or markdown, \
+             or json, yaml, toml or ndjson",
+            format
+        );
+    }
+
+    cli.perspective = cli.perspective.map(|s| s.to_uppercase_smolstr());
+    let mut currencies = cli
+        .currencies
+        .iter()
+        .map(|x| x.to_uppercase_smolstr())
+        .collect::<Vec<_>>();
+    // Correlations are relative to the perspective, which makes EUR one of the currencies
+    if let (false, Some(Action::Correlate(_)), Some(_)) =
+        (currencies.is_empty(), &cli.action, &cli.perspective)
+    {
+        currencies.push("EUR".into());
     }
 
     let mut header_description = HeaderDescription::new();
-    let use_cache = !cli.no_cache;
-    let view = cli
-        .show_days
-        .to_view()
-        .context("It doesn't make any sence to fetch 0 days right?")?;
-    let mut cache = if use_cache { Cache::load(&view) } else { None };
-    let cache_ok = cache.as_ref().map_or_else(
-        || false,
-        |c| c.get_cache_line().map_or_else(|| false, |cl| cl.is_valid()),
-    );
-    let mut parsed = if cache_ok {
-        // These are safe unwraps
-        cache
-            .as_ref()
-            .unwrap()
-            .get_cache_line()
-            .unwrap()
-            .exchange_rate_results
-            .clone()
+    let date_range = cli.date_range();
+    if let (Some(from), Some(to)) = (date_range.from, date_range.to)
+        && from > to
+    {
+        bail!("--from ({}) is after --to ({})", from, to);
+    }
+    let mut view = if let Some(date) = cli.date {
+        EcbClient::view_for_date(date)?
+    } else if date_range.is_unbounded() {
+        cli.show_days
+            .to_view()
+            .context("It doesn't make any sence to fetch 0 days right?")?
     } else {
-        let parsed = get_and_parse(view.to_ecb_url())
-            .await
-            .context("Failed to get/parse data from ECB")?;
-        if !cache_ok {
-            let not_equal_cache = cache.as_ref().map_or_else(
-                || true,
-                |cache_local| {
-                    cache_local
-                        .get_cache_line()
-                        .map_or_else(|| true, |cache_line| cache_line == &parsed)
-                },
-            );
-
-            if not_equal_cache && let Some(cache_safe) = cache.as_mut() {
-                let cache_line = CacheLine::new(parsed.clone());
-                cache_safe.set_cache_line(cache_line);
-                cache_safe.save()?;
-            }
-        }
-        parsed
+        date_range.to_view(TargetCalendar::latest_publication_day(Frankfurt::now()))
     };
 
-    cli.perspective = cli.perspective.map(|s| s.to_uppercase_smolstr());
+    // Every day is needed at once only when the days are compared, resampled, filled in or picked
+    let needs_all_days = cli.show_change
+        || cli.fill.is_some()
+        || cli.resample.is_some()
+        || cli.date.is_some()
+        || (date_range.is_unbounded() && cli.show_days.to_option().is_some());
+    if let (FormatOption::Ndjson, None, false) = (cli.command, &cli.action, needs_all_days) {
+        return stream_ndjson(&cli, &client, &view, &date_range, &currencies).await;
+    }
+
+    // Changes and the start of a filled window need the publication before it
+    if cli.show_change || cli.fill.is_some() {
+        view = view_with_previous(view, cli.date.or(date_range.from));
+    }
+    let (mut parsed, freshness) = fetch(&client, &view).await?;
+    let history = (cli.show_change || cli.fill.is_some()).then(|| parsed.clone());
+
+    if !date_range.is_unbounded() {
+        filter_date_range(&mut parsed, &date_range);
+    }
+
+    if let Some(date) = cli.date {
+        parsed = vec![select_date(&parsed, &date)?];
+    }
+
+    // The publications are newest first, until reversed
+    let show_days = if date_range.is_unbounded() {
+        cli.show_days.to_option()
+    } else {
+        None
+    };
+    if let Some(n) = show_days {
+        parsed.truncate(n);
+    }
+    parsed.reverse();
+
     if let Some(currency) = cli.perspective.as_ref() {
         header_description.replace_eur(currency);
-        change_perspective(&mut parsed, currency)
-            .context("The currency wasn't in the data from the ECB!")?;
     }
-
     if cli.should_invert {
-        invert_rates(&mut parsed);
         header_description.invert();
     }
+    transform(&cli, &mut parsed)?;
 
-    round(&mut parsed, cli.max_decimals);
+    if let Some(period) = cli.resample {
+        parsed = resample(&parsed, period, cli.aggregate);
+    }
 
-    if !cli.currencies.is_empty() {
-        let currencies = cli
-            .currencies
-            .iter()
-            .map(|x| x.to_uppercase_smolstr())
-            .collect::<Vec<_>>();
+    if let (true, Some(history)) = (cli.show_change, history.as_ref()) {
+        // Resampled periods are compared with the period before them
+        if cli.resample.is_some() {
+            let periods = parsed.clone();
+            with_changes(&periods, &mut parsed, |_| Ok(()))?;
+        } else {
+            with_changes(history, &mut parsed, |previous| transform(&cli, previous))?;
+        }
+    }
 
+    if !currencies.is_empty() {
         filter_currencies(&mut parsed, &currencies);
     }
 
-    parsed.reverse();
-    let parsed = match cli.show_days.to_option() {
-        Some(n) => {
-            if parsed.len() <= n {
-                parsed.as_slice()
-            } else {
-                &parsed[0..n]
+    // Statistics and correlations are of the published rates, before they're filled in or rounded
+    if let Some(Action::Stats(_)) = cli.action {
+        let output = stats_output(&cli, &parsed, &header_description)?;
+        write_output(&cli, &output)?;
+        return Ok(freshness);
+    }
+    if let Some(Action::Correlate(_)) = cli.action {
+        let output = correlate_output(&cli, &parsed, &header_description)?;
+        write_output(&cli, &output)?;
+        return Ok(freshness);
+    }
+
+    if let Some(policy) = cli.fill {
+        // The window is filled up to its bounds, from the publications around it
+        let dates = parsed
+            .iter()
+            .filter_map(|x| x.time.parse::<NaiveDate>().ok());
+        let bounds = DateRange::new(
+            date_range.from.or(dates.clone().min()),
+            date_range.to.or(dates.max()),
+        );
+        if let (None, Some(history)) = (cli.resample, history.as_ref()) {
+            let mut neighbours = neighbours(history, &bounds);
+            transform(&cli, &mut neighbours)?;
+            if !currencies.is_empty() {
+                filter_currencies(&mut neighbours, &currencies);
             }
+            parsed.extend(neighbours);
+        }
+        parsed = fill_gaps(&parsed, policy, &bounds);
+    }
+
+    round(&mut parsed, cli.max_decimals);
+    let parsed = parsed.as_slice();
+
+    let output = match cli.command {
+        FormatOption::Json => json::render(parsed, !cli.no_time, cli.compact),
+        FormatOption::Yaml => yaml::render(parsed, !cli.no_time),
+        FormatOption::Toml => toml::render(parsed, "days", !cli.no_time),
+        FormatOption::Ndjson => {
+            let mut writer = output_writer(&cli)?;
+            let written =
+                ndjson::write(&mut writer, parsed, cli.layout, !cli.no_time, &cli.sort_by);
+            written_or_broken_pipe(written)?;
+            return Ok(freshness);
+        }
+        FormatOption::Plain => plain::render(
+            parsed,
+            &header_description,
+            !cli.no_time,
+            &cli.sort_by,
+            cli.layout,
+        ),
+        FormatOption::Csv | FormatOption::Tsv => delimited(&cli).render(parsed, &cli.sort_by),
+        FormatOption::Markdown => {
+            let mut markdown = Markdown::new(cli.layout);
+            markdown.time = !cli.no_time;
+            markdown.render(parsed, &header_description, &cli.sort_by)
+        }
+        FormatOption::Ledger => ledger::render(parsed, &header_description, &cli.sort_by),
+        FormatOption::Beancount => {
+            beancount(&cli)?.render(parsed, &header_description, &cli.sort_by)
+        }
+        FormatOption::Chart => {
+            let mut chart = Chart::new();
+            chart.ascii = cli.ascii;
+            chart.render(parsed, &header_description, &cli.sort_by)
+        }
+        FormatOption::Svg => {
+            let mut svg = SvgChart::new();
+            svg.rebase = cli.rebase;
+            svg.render(parsed, &header_description, &cli.sort_by)
+        }
+        FormatOption::Prometheus => {
+            let mut prometheus = Prometheus::new();
+            prometheus.time = !cli.no_time;
+            prometheus.render(parsed, &header_description, &cli.sort_by)
+        }
+        FormatOption::Html => {
+            let mut html = Html::new(cli.max_decimals);
+            html.time = !cli.no_time;
+            html.render(parsed, &header_description, &cli.sort_by)
+        }
+    };
+
+    write_output(&cli, &output)?;
+    Ok(freshness)
+}
+
+async fn convert_main(
+    cli: &Cli,
+    client: &EcbClient,
+    args: &ConvertArgs,
+) -> anyhow::Result<Freshness> {
+    let view = match cli.date {
+        Some(date) => EcbClient::view_for_date(date)?,
+        None => View::TODAY,
+    };
+    let (parsed, freshness) = fetch(client, &view).await?;
+    let exchange_rate = match cli.date {
+        Some(date) => select_date(&parsed, &date)?,
+        None => parsed
+            .iter()
+            .max_by(|a, b| a.time.cmp(&b.time))
+            .cloned()
+            .ok_or(Error::NoRates)?,
+    };
+
+    let from = args.source.to_uppercase_smolstr();
+    let to = args
+        .targets
+        .iter()
+        .map(|x| x.to_uppercase_smolstr())
+        .collect::<Vec<_>>();
+    let mut conversion = convert(&exchange_rate, args.amount, &from, &to)?;
+    round_conversion(&mut conversion, cli.max_decimals);
+
+    let output = match cli.command {
+        FormatOption::Json => json::render(&conversion, !cli.no_time, cli.compact),
+        FormatOption::Yaml => yaml::render(&conversion, !cli.no_time),
+        FormatOption::Toml => toml::render(&conversion, "conversion", !cli.no_time),
+        // A conversion is a single record
+        FormatOption::Ndjson => json::render(&conversion, !cli.no_time, true),
+        FormatOption::Plain => plain::render_conversion(&conversion, !cli.no_time),
+        FormatOption::Csv | FormatOption::Tsv => delimited(cli).render_conversion(&conversion),
+        FormatOption::Markdown => {
+            let mut markdown = Markdown::new(cli.layout);
+            markdown.time = !cli.no_time;
+            markdown.render_conversion(&conversion)
         }
-        None => parsed.as_slice(),
+        FormatOption::Ledger => ledger::render_conversion(&conversion),
+        FormatOption::Beancount => beancount(cli)?.render_conversion(&conversion),
+        FormatOption::Chart | FormatOption::Svg => {
+            bail!("A conversion can't be drawn as a chart")
+        }
+        FormatOption::Html => bail!("A conversion can't be written as an HTML report"),
+        FormatOption::Prometheus => bail!("A conversion can't be written as Prometheus gauges"),
     };
 
+    write_output(cli, &output)?;
+    Ok(freshness)
+}
+
+fn stats_output(
+    cli: &Cli,
+    exchange_rate_results: &[ExchangeRateResult],
+    header_description: &HeaderDescription,
+) -> anyhow::Result<String> {
+    let mut stats = stats(exchange_rate_results, &cli.sort_by);
+    round_stats(&mut stats, cli.max_decimals);
+
     let output = match cli.command {
-        FormatOption::Json => {
-            let mut json_values = parsed
-                .iter()
-                .map(|x| serde_json::to_value(x).expect("Failed to parse content as JSON value"))
-                .collect::<Vec<_>>();
-
-            if cli.no_time {
-                json_values
-                    .iter_mut()
-                    .filter_map(|json_value| json_value.as_object_mut())
-                    .for_each(|map| {
-                        map.remove_entry("time");
-                    });
+        FormatOption::Json => json::render(&stats, !cli.no_time, cli.compact),
+        FormatOption::Yaml => yaml::render(&stats, !cli.no_time),
+        FormatOption::Toml => toml::render(&stats, "currencies", !cli.no_time),
+        FormatOption::Plain => plain::render_stats(&stats, header_description),
+        FormatOption::Csv | FormatOption::Tsv => delimited(cli).render_stats(&stats),
+        format => bail!(
+            "Statistics can't be shown as {}, use plain, json, yaml, toml, csv or tsv",
+            name(format)
+        ),
+    };
+    Ok(output)
+}
+
+fn correlate_output(
+    cli: &Cli,
+    exchange_rate_results: &[ExchangeRateResult],
+    header_description: &HeaderDescription,
+) -> anyhow::Result<String> {
+    // A correlation needs at least two returns, which are between three publications
+    if exchange_rate_results.len() < 3 {
+        bail!(
+            "Correlations need at least 3 publications, but got {}. Select more, e.g. with -s 90",
+            exchange_rate_results.len()
+        );
+    }
+    let mut correlations = correlate(exchange_rate_results, &cli.sort_by);
+    round_correlations(&mut correlations, cli.max_decimals);
+
+    let output = match cli.command {
+        FormatOption::Json => json::render(&correlations, !cli.no_time, cli.compact),
+        FormatOption::Yaml => yaml::render(&correlations, !cli.no_time),
+        FormatOption::Plain => plain::render_correlations(&correlations, header_description),
+        FormatOption::Csv | FormatOption::Tsv => delimited(cli).render_correlations(&correlations),
+        format => bail!(
+            "Correlations can't be shown as {}, use plain, json, yaml, csv or tsv",
+            name(format)
+        ),
+    };
+    Ok(output)
+}
+
+/// Recalculates the rates to the perspective, and inverts them, if asked for
+fn transform(cli: &Cli, exchange_rate_results: &mut [ExchangeRateResult]) -> ecb_rates::Result<()> {
+    if let Some(currency) = cli.perspective.as_ref() {
+        change_perspective(exchange_rate_results, currency)?;
+    }
+    if cli.should_invert {
+        invert_rates(exchange_rate_results);
+    }
+    Ok(())
+}
+
+/// Whether the output has room for the changes of `--show-change`
+fn shows_changes(cli: &Cli) -> bool {
+    match cli.command {
+        FormatOption::Json | FormatOption::Yaml | FormatOption::Toml | FormatOption::Ndjson => true,
+        FormatOption::Plain | FormatOption::Csv | FormatOption::Tsv | FormatOption::Markdown => {
+            cli.layout == Layout::Long
+        }
+        _ => false,
+    }
+}
+
+/// The name of an option value, as it's passed on the command line
+fn name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|x| x.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// The view which also contains the publication before the first one, to compare it with.
+/// Without a first date, the latest publication is compared with the one before it.
+fn view_with_previous(view: View, first: Option<NaiveDate>) -> View {
+    let Some(first) = first else {
+        return view.max(View::HistDays90);
+    };
+    TargetCalendar::last_publication_day(first)
+        .and_then(|first| first.pred_opt())
+        .and_then(|before| EcbClient::view_for_date(before).ok())
+        .map_or(view, |previous| view.max(previous))
+}
+
+/// Fetches the view, warning on stderr if the rates are outdated
+async fn fetch(
+    client: &EcbClient,
+    view: &View,
+) -> anyhow::Result<(Vec<ExchangeRateResult>, Freshness)> {
+    let Fetched {
+        exchange_rate_results,
+        freshness,
+    } = client.fetch(view).await?;
+    warn_if_stale(&freshness);
+    Ok((exchange_rate_results, freshness))
+}
+
+/// Writes every day of the view as NDJSON as soon as it's read, so that not even `-s all` is held
+/// in memory
+async fn stream_ndjson(
+    cli: &Cli,
+    client: &EcbClient,
+    view: &View,
+    date_range: &DateRange,
+    currencies: &[SmolStr],
+) -> anyhow::Result<Freshness> {
+    let mut writer = output_writer(cli)?;
+    let mut written = Ok(());
+    let freshness = client
+        .for_each(view, |exchange_rate| -> anyhow::Result<()> {
+            let in_range = exchange_rate
+                .time
+                .parse::<NaiveDate>()
+                .is_ok_and(|date| date_range.contains(&date));
+            // Once the reader stops reading, the remaining days are skipped
+            if !in_range || written.is_err() {
+                return Ok(());
+            }
+            let mut day = [exchange_rate];
+            transform(cli, &mut day)?;
+            if !currencies.is_empty() {
+                filter_currencies(&mut day, currencies);
             }
+            round(&mut day, cli.max_decimals);
+            written =
+                ndjson::write_day(&mut writer, &day[0], cli.layout, !cli.no_time, &cli.sort_by);
+            Ok(())
+        })
+        .await?;
+    warn_if_stale(&freshness);
+    written_or_broken_pipe(written.and_then(|_| writer.flush()))?;
+    Ok(freshness)
+}
 
-            let to_string_json = if cli.compact {
-                serde_json::to_string
-            } else {
-                serde_json::to_string_pretty
-            };
-            to_string_json(&json_values).expect("Failed to parse content as JSON")
-        }
-        FormatOption::Plain => {
-            let rates = parsed
-                .iter()
-                .map(|x| {
-                    let mut t: TableRef = x.into();
-                    if cli.no_time {
-                        t.disable_header();
-                    }
-                    t.sort(&cli.sort_by);
-                    t.to_string()
-                })
-                .collect::<Vec<_>>()
-                .join("\n");
-            let mut s = header_description.to_string();
-            s.push_str(&rates);
-            s
+fn warn_if_stale(freshness: &Freshness) {
+    if let Freshness::Stale {
+        fetched_at,
+        latest,
+        error,
+    } = freshness
+    {
+        if let Some(e) = error {
+            eprintln!("Warning: {:#}", e);
         }
+        let latest = latest.map_or_else(|| "unknown".to_string(), |date| date.to_string());
+        eprintln!(
+            "Warning: Using outdated cached rates, last published {} and fetched {}",
+            latest,
+            fetched_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        );
+    }
+}
+
+fn select_date(
+    exchange_rate_results: &[ExchangeRateResult],
+    date: &NaiveDate,
+) -> anyhow::Result<ExchangeRateResult> {
+    let (used_date, exchange_rate) =
+        rate_on_or_before(exchange_rate_results, date).ok_or(Error::NoPublication(*date))?;
+    if &used_date != date {
+        eprintln!(
+            "No rates were published on {}, using the rates from {} instead",
+            date, used_date
+        );
+    }
+    Ok(exchange_rate.clone())
+}
+
+fn delimited(cli: &Cli) -> Delimited {
+    let mut delimited = match cli.command {
+        FormatOption::Tsv => Delimited::tsv(cli.layout),
+        _ => Delimited::csv(cli.layout),
     };
+    delimited.header = !cli.no_header;
+    delimited
+}
 
-    println!("{}", &output);
-    Ok(())
+fn beancount(cli: &Cli) -> anyhow::Result<Beancount> {
+    let mut beancount = Beancount::new();
+    if let Some(path) = cli.skip_existing.as_ref() {
+        // The journal doesn't exist before the first run
+        match fs::read_to_string(path) {
+            Ok(journal) => beancount.skip_existing(&journal),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        }
+    }
+    Ok(beancount)
+}
+
+/// Opens the file given with `--output`, or else stdout
+fn output_writer(cli: &Cli) -> anyhow::Result<Box<dyn Write>> {
+    let writer: Box<dyn Write> = match cli.output.as_ref() {
+        Some(path) => {
+            let file = fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            Box::new(io::BufWriter::new(file))
+        }
+        None => Box::new(io::BufWriter::new(io::stdout().lock())),
+    };
+    Ok(writer)
+}
+
+fn write_output(cli: &Cli, output: &str) -> anyhow::Result<()> {
+    let mut writer = output_writer(cli)?;
+    let written = writeln!(writer, "{}", output).and_then(|_| writer.flush());
+    written_or_broken_pipe(written)
+}
+
+/// The reader, e.g. `head`, is allowed to stop reading early
+fn written_or_broken_pipe(written: io::Result<()>) -> anyhow::Result<()> {
+    match written {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            Err(e).context("Failed to write the output")
+        }
+        _ => Ok(()),
+    }
 }
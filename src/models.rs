@@ -1,9 +1,137 @@
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analysis::Aggregate;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ExchangeRateResult {
     pub time: SmolStr,
     pub rates: HashMap<SmolStr, f64>,
+    /// The change of the rates since the previous publication, if asked for
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub changes: HashMap<SmolStr, RateChange>,
+    /// Whether the rates were filled in for a day without a publication
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub filled: bool,
+    /// Why nothing was published on a filled day, e.g. `weekend` or `Good Friday`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<SmolStr>,
+    /// The label of a resampled period, e.g. `2025-01`. The time is then its last publication.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<SmolStr>,
+    /// Every aggregate of the rates in a resampled period
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub aggregates: HashMap<SmolStr, Aggregates>,
+}
+
+impl ExchangeRateResult {
+    pub fn new(time: SmolStr, rates: HashMap<SmolStr, f64>) -> Self {
+        Self {
+            time,
+            rates,
+            changes: HashMap::new(),
+            filled: false,
+            reason: None,
+            period: None,
+            aggregates: HashMap::new(),
+        }
+    }
+
+    /// The period of resampled rates, or else the date of publication
+    pub fn label(&self) -> &str {
+        self.period.as_deref().unwrap_or(&self.time)
+    }
+}
+
+/// The rates of a currency, reduced over a resampled period
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Aggregates {
+    pub mean: f64,
+    /// The last rate of the period
+    pub close: f64,
+    /// The first rate of the period
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+}
+
+impl Aggregates {
+    pub fn get(&self, aggregate: Aggregate) -> f64 {
+        match aggregate {
+            Aggregate::Mean => self.mean,
+            Aggregate::Close => self.close,
+            Aggregate::Open => self.open,
+            Aggregate::High => self.high,
+            Aggregate::Low => self.low,
+        }
+    }
+
+    /// The mean, close, open, high and low rates
+    pub fn values(&self) -> [f64; 5] {
+        [self.mean, self.close, self.open, self.high, self.low]
+    }
+
+    pub fn map(&self, f: impl Fn(f64) -> f64) -> Self {
+        Self {
+            mean: f(self.mean),
+            close: f(self.close),
+            open: f(self.open),
+            high: f(self.high),
+            low: f(self.low),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct RateChange {
+    pub change: f64,
+    pub change_percent: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Conversion {
+    pub time: SmolStr,
+    pub amount: f64,
+    pub from: SmolStr,
+    pub to: Vec<ConvertedAmount>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConvertedAmount {
+    pub currency: SmolStr,
+    pub rate: f64,
+    pub amount: f64,
+}
+
+/// A rate on a given date
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Observation {
+    pub time: SmolStr,
+    pub rate: f64,
+}
+
+/// Summary statistics of the rates of a currency over a window
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Stats {
+    pub currency: SmolStr,
+    pub count: usize,
+    pub first: Observation,
+    pub last: Observation,
+    pub min: Observation,
+    pub max: Observation,
+    pub mean: f64,
+    pub median: f64,
+    /// The sample standard deviation
+    pub stddev: f64,
+    pub change: f64,
+    pub change_percent: f64,
+}
+
+/// The correlation of the daily log returns of a currency with those of every currency
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Correlation {
+    pub currency: SmolStr,
+    /// `None` when the currencies have less than two returns in common, or one of them never moves
+    pub correlations: BTreeMap<SmolStr, Option<f64>>,
 }
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use smol_str::{SmolStr, StrExt};
+
+use crate::HeaderDescription;
+use crate::cli::SortBy;
+use crate::models::{Conversion, ExchangeRateResult};
+
+use super::ledger::{Price, conversion_prices, prices};
+
+/// Renders rates as Beancount price directives, e.g. `2024-05-02 price EUR 1.0729 USD`
+#[derive(Default)]
+pub struct Beancount {
+    /// Prices already in the journal, as (date, commodity, currency)
+    existing: HashSet<(SmolStr, SmolStr, SmolStr)>,
+}
+
+impl Beancount {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips every price which is already in the given journal
+    pub fn skip_existing(&mut self, journal: &str) {
+        for line in journal.lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            if let [date, "price", commodity, _rate, currency, ..] = fields[..] {
+                self.existing
+                    .insert((date.into(), commodity.into(), currency.into()));
+            }
+        }
+    }
+
+    pub fn render(
+        &self,
+        exchange_rate_results: &[ExchangeRateResult],
+        header_description: &HeaderDescription,
+        sort_by: &SortBy,
+    ) -> String {
+        self.directives(prices(exchange_rate_results, header_description, sort_by))
+    }
+
+    pub fn render_conversion(&self, conversion: &Conversion) -> String {
+        self.directives(conversion_prices(conversion))
+    }
+
+    fn directives(&self, prices: Vec<Price>) -> String {
+        prices
+            .into_iter()
+            .filter_map(|price| {
+                let key = (
+                    SmolStr::new(price.time),
+                    commodity(price.commodity),
+                    commodity(price.currency),
+                );
+                if self.existing.contains(&key) {
+                    return None;
+                }
+                let (time, commodity, currency) = key;
+                Some(format!(
+                    "{} price {} {} {}",
+                    time, commodity, price.rate, currency
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Beancount commodities are upper case, and may only contain letters, digits and `'._-`.
+/// They have to start with a letter and end with a letter or a digit.
+fn commodity(name: &str) -> SmolStr {
+    let name = name
+        .to_uppercase_smolstr()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "'._-".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let name = name.trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+    match name.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => name.into(),
+        _ => format!("C{}", name).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_commodity() {
+        assert_eq!(commodity("usd"), "USD");
+        assert_eq!(commodity("BTC/EUR"), "BTC-EUR");
+        assert_eq!(commodity("1INCH"), "C1INCH");
+    }
+
+    #[test]
+    fn test_skip_existing() {
+        let exchange_rate_results = [ExchangeRateResult::new(
+            "2024-05-02".into(),
+            HashMap::from([("USD".into(), 1.0729), ("SEK".into(), 11.6)]),
+        )];
+        let mut beancount = Beancount::new();
+        beancount.skip_existing(
+            "option \"operating_currency\" \"EUR\"\n\
+             2024-05-02 price EUR 1.0729 USD ; from the ECB\n",
+        );
+        let rendered = beancount.render(
+            &exchange_rate_results,
+            &HeaderDescription::new(),
+            &SortBy::Currency,
+        );
+        assert_eq!(rendered, "2024-05-02 price EUR 11.6 SEK");
+    }
+}
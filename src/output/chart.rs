@@ -0,0 +1,361 @@
+use colored::{ColoredString, Colorize};
+use smol_str::SmolStr;
+
+use crate::HeaderDescription;
+use crate::cli::SortBy;
+use crate::models::ExchangeRateResult;
+use crate::table::TableMatrix;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const ASCII_SPARKS: [char; 8] = ['_', '.', '-', ':', '=', '+', '*', '#'];
+const ASCII_POINTS: [char; 2] = ['*', 'o'];
+
+/// Draws the rate history in the terminal: a sparkline per currency, followed by a line chart
+/// when there are one or two currencies.
+pub struct Chart {
+    /// Width of the sparklines and the line chart, in characters
+    pub width: usize,
+    /// Height of the line chart, in lines
+    pub height: usize,
+    /// Only use ASCII, rather than block and braille characters
+    pub ascii: bool,
+}
+
+impl Default for Chart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The rates of one currency, in chronological order, as (index of the date, rate)
+struct Series<'a> {
+    currency: &'a str,
+    points: Vec<(usize, f64)>,
+}
+
+impl Series<'_> {
+    fn min(&self) -> f64 {
+        self.points
+            .iter()
+            .map(|x| x.1)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn max(&self) -> f64 {
+        self.points
+            .iter()
+            .map(|x| x.1)
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+}
+
+impl Chart {
+    pub fn new() -> Self {
+        Self {
+            width: 60,
+            height: 12,
+            ascii: false,
+        }
+    }
+
+    pub fn render(
+        &self,
+        exchange_rate_results: &[ExchangeRateResult],
+        header_description: &HeaderDescription,
+        sort_by: &SortBy,
+    ) -> String {
+        let mut exchange_rate_results = exchange_rate_results.to_vec();
+        exchange_rate_results.sort_by(|a, b| a.time.cmp(&b.time));
+        let mut table = TableMatrix::from(&exchange_rate_results[..]);
+        table.sort(sort_by);
+
+        let dates = table.get_columns();
+        let series = table
+            .get_rows()
+            .iter()
+            .map(|(currency, values)| Series {
+                currency,
+                points: values
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, value)| value.map(|x| (i, x)))
+                    .collect(),
+            })
+            .filter(|series| !series.points.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut s = header_description.to_string();
+        let Some(label_width) = series.iter().map(|x| x.currency.len()).max() else {
+            return s;
+        };
+        for series in series.iter() {
+            s.push_str(&self.sparkline_row(series, label_width));
+            s.push('\n');
+        }
+        if (1..=2).contains(&series.len()) && dates.len() > 1 {
+            s.push('\n');
+            s.push_str(&self.line_chart(&series, dates.first(), dates.last(), dates.len()));
+        }
+        s
+    }
+
+    fn sparkline_row(&self, series: &Series, label_width: usize) -> String {
+        let values = series.points.iter().map(|x| x.1).collect::<Vec<_>>();
+        let first = values[0];
+        let last = values[values.len() - 1];
+        format!(
+            " {}  {}  {} -> {}  (min {}, max {})",
+            format!("{:<label_width$}", series.currency).bold().green(),
+            self.sparkline(&values),
+            first,
+            last.to_string().bold(),
+            series.min(),
+            series.max(),
+        )
+    }
+
+    /// Draws the values as a sparkline, averaging them into buckets if there are too many
+    pub fn sparkline(&self, values: &[f64]) -> String {
+        let values = shrink(values, self.width);
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let sparks = if self.ascii { ASCII_SPARKS } else { SPARKS };
+        values
+            .iter()
+            .map(|value| sparks[scale(*value, min, max, sparks.len())])
+            .collect()
+    }
+
+    /// Plots one or two series, with the axis of the first one on the left, and of the second
+    /// one on the right
+    fn line_chart(
+        &self,
+        series: &[Series],
+        first_date: Option<&SmolStr>,
+        last_date: Option<&SmolStr>,
+        date_count: usize,
+    ) -> String {
+        let (dots_x, dots_y) = if self.ascii { (1, 1) } else { (2, 4) };
+        let mut canvas = Canvas::new(self.width * dots_x, self.height * dots_y);
+        for (index, series) in series.iter().enumerate() {
+            let (min, max) = (series.min(), series.max());
+            let (width, height) = (canvas.width, canvas.height);
+            let point = |(i, value): (usize, f64)| {
+                let x = i * (width - 1) / (date_count - 1);
+                let y = height - 1 - scale(value, min, max, height);
+                (x, y)
+            };
+            for pair in series.points.windows(2) {
+                canvas.line(point(pair[0]), point(pair[1]), index);
+            }
+            if let [only] = series.points[..] {
+                let (x, y) = point(only);
+                canvas.set(x, y, index);
+            }
+        }
+
+        let labels = series
+            .iter()
+            .map(|x| (x.max().to_string(), x.min().to_string()))
+            .collect::<Vec<_>>();
+        let left_width = labels[0].0.len().max(labels[0].1.len());
+        let lines = if self.ascii {
+            canvas.ascii_lines()
+        } else {
+            canvas.braille_lines(self.height)
+        };
+
+        let mut rows = Vec::with_capacity(self.height + 2);
+        for (row, line) in lines.into_iter().enumerate() {
+            let label = |(max, min): &(String, String)| match row {
+                0 => max.clone(),
+                row if row == self.height - 1 => min.clone(),
+                _ => String::new(),
+            };
+            let mut text = format!(" {:>left_width$} |{}|", label(&labels[0]), line);
+            if let Some(right) = labels.get(1) {
+                text.push(' ');
+                text.push_str(&label(right));
+            }
+            rows.push(text);
+        }
+
+        let first_date = first_date.map(|x| x.as_str()).unwrap_or_default();
+        let last_date = last_date.map(|x| x.as_str()).unwrap_or_default();
+        let padding = (self.width + 2).saturating_sub(first_date.len() + last_date.len());
+        rows.push(format!(
+            " {}{}{}{}",
+            " ".repeat(left_width + 1),
+            first_date,
+            " ".repeat(padding),
+            last_date
+        ));
+
+        let legend = series
+            .iter()
+            .enumerate()
+            .map(|(index, series)| {
+                let marker = if self.ascii {
+                    ASCII_POINTS[index].to_string()
+                } else {
+                    "━━".to_string()
+                };
+                format!("{} {}", paint(&marker, index), series.currency)
+            })
+            .collect::<Vec<_>>();
+        rows.push(format!(
+            " {}{}",
+            " ".repeat(left_width + 2),
+            legend.join("   ")
+        ));
+        rows.join("\n")
+    }
+}
+
+/// Averages the values into at most `n` buckets
+fn shrink(values: &[f64], n: usize) -> Vec<f64> {
+    if values.len() <= n {
+        return values.to_vec();
+    }
+    (0..n)
+        .map(|bucket| {
+            let start = bucket * values.len() / n;
+            let end = (bucket + 1) * values.len() / n;
+            values[start..end].iter().sum::<f64>() / (end - start) as f64
+        })
+        .collect()
+}
+
+/// Scales the value into a step in `0..steps`
+fn scale(value: f64, min: f64, max: f64, steps: usize) -> usize {
+    if max <= min {
+        return steps / 2;
+    }
+    let step = ((value - min) / (max - min) * (steps - 1) as f64).round() as usize;
+    step.min(steps - 1)
+}
+
+fn paint(s: &str, series: usize) -> ColoredString {
+    match series {
+        0 => s.cyan(),
+        _ => s.yellow(),
+    }
+}
+
+/// A grid of dots, each remembering the first series which was drawn on it
+struct Canvas {
+    width: usize,
+    height: usize,
+    dots: Vec<Option<usize>>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            dots: vec![None; width * height],
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, series: usize) {
+        let dot = &mut self.dots[y * self.width + x];
+        dot.get_or_insert(series);
+    }
+
+    /// Bresenham's line algorithm
+    fn line(&mut self, (x0, y0): (usize, usize), (x1, y1): (usize, usize), series: usize) {
+        let (x0, y0, x1, y1) = (x0 as isize, y0 as isize, x1 as isize, y1 as isize);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let (mut x, mut y, mut error) = (x0, y0, dx + dy);
+        loop {
+            self.set(x as usize, y as usize, series);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn ascii_lines(&self) -> Vec<String> {
+        self.dots
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|dot| match dot {
+                        Some(series) => paint(&ASCII_POINTS[*series].to_string(), *series),
+                        None => " ".normal(),
+                    })
+                    .map(|x| x.to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Packs 2x4 dots into every braille character
+    fn braille_lines(&self, lines: usize) -> Vec<String> {
+        const BITS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+        (0..lines)
+            .map(|line| {
+                (0..self.width / 2)
+                    .map(|column| {
+                        let mut bits = 0;
+                        let mut series = None;
+                        for (dx, column_bits) in BITS.iter().enumerate() {
+                            for (dy, bit) in column_bits.iter().enumerate() {
+                                let (x, y) = (column * 2 + dx, line * 4 + dy);
+                                if let Some(dot) = self.dots[y * self.width + x] {
+                                    bits |= bit;
+                                    series.get_or_insert(dot);
+                                }
+                            }
+                        }
+                        // These are safe unwraps, since every braille pattern is a char
+                        let c = char::from_u32(0x2800 + bits).unwrap().to_string();
+                        match series {
+                            Some(series) => paint(&c, series).to_string(),
+                            None => c,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::strip_colors;
+
+    #[test]
+    fn test_sparkline() {
+        let chart = Chart::new();
+        assert_eq!(chart.sparkline(&[1.0, 2.0, 3.0, 4.0, 3.0]), "▁▃▆█▆");
+        assert_eq!(chart.sparkline(&[2.0, 2.0]), "▅▅");
+        assert_eq!(shrink(&[1.0, 3.0, 5.0, 7.0], 2), vec![2.0, 6.0]);
+    }
+
+    #[test]
+    fn test_braille_line() {
+        let mut canvas = Canvas::new(2, 4);
+        canvas.line((0, 3), (1, 0), 0);
+        assert_eq!(canvas.dots.iter().filter(|x| x.is_some()).count(), 4);
+        let lines = canvas.braille_lines(1);
+        assert_eq!(
+            lines.iter().map(|x| strip_colors(x)).collect::<Vec<_>>(),
+            ["⡜"]
+        );
+    }
+}
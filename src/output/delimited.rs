@@ -0,0 +1,396 @@
+use std::collections::BTreeSet;
+
+use smol_str::SmolStr;
+
+use crate::cli::{Layout, SortBy};
+use crate::models::{Conversion, Correlation, ExchangeRateResult, Stats};
+use crate::table::TableMatrix;
+
+/// The columns of [crate::models::Aggregates::values]
+const AGGREGATES: [&str; 5] = ["mean", "close", "open", "high", "low"];
+
+const CHANGES: [&str; 2] = ["change", "change_percent"];
+const FILLED: [&str; 2] = ["filled", "reason"];
+
+/// Renders rates as delimiter separated values, e.g. CSV or TSV.
+/// The date is the key of every record, so there's always a date column.
+pub struct Delimited {
+    pub delimiter: char,
+    pub layout: Layout,
+    /// Whether to begin with a row of column names
+    pub header: bool,
+}
+
+impl Delimited {
+    pub fn csv(layout: Layout) -> Self {
+        Self {
+            delimiter: ',',
+            layout,
+            header: true,
+        }
+    }
+
+    pub fn tsv(layout: Layout) -> Self {
+        Self {
+            delimiter: '\t',
+            ..Self::csv(layout)
+        }
+    }
+
+    /// Resampled rates get a period column, and in the long layout a column per aggregate.
+    /// Changes get a `change` and `change_percent` column in the long layout.
+    /// Filled in days get a `filled` and `reason` column, or row in the matrix layout.
+    pub fn render(&self, exchange_rate_results: &[ExchangeRateResult], sort_by: &SortBy) -> String {
+        let resampled = exchange_rate_results.iter().any(|x| x.period.is_some());
+        let changed = exchange_rate_results.iter().any(|x| !x.changes.is_empty());
+        let filled = exchange_rate_results.iter().any(|x| x.filled);
+        let trailing_columns = if filled { FILLED.as_slice() } else { &[] };
+        let mut lines = Vec::new();
+        match self.layout {
+            Layout::Long => {
+                if self.header {
+                    let mut columns = vec!["currency", "rate"];
+                    if resampled {
+                        columns.extend(AGGREGATES);
+                    }
+                    if changed {
+                        columns.extend(CHANGES);
+                    }
+                    columns.extend(trailing_columns);
+                    lines.push(self.line(Self::leading_columns(resampled).chain(columns)));
+                }
+                let comparer = sort_by.get_comparer();
+                for exchange_rate in exchange_rate_results {
+                    let mut rates = exchange_rate
+                        .rates
+                        .iter()
+                        .map(|(currency, rate)| (currency.as_str(), *rate))
+                        .collect::<Vec<_>>();
+                    rates.sort_by(comparer);
+                    for (currency, rate) in rates {
+                        let mut fields = vec![currency.to_string(), rate.to_string()];
+                        if resampled {
+                            let values = exchange_rate.aggregates.get(currency).map(|x| x.values());
+                            fields.extend(
+                                (0..AGGREGATES.len())
+                                    .map(|i| values.map(|x| x[i].to_string()).unwrap_or_default()),
+                            );
+                        }
+                        if changed {
+                            let change = exchange_rate.changes.get(currency);
+                            fields.extend([
+                                change.map(|x| x.change.to_string()).unwrap_or_default(),
+                                change
+                                    .map(|x| x.change_percent.to_string())
+                                    .unwrap_or_default(),
+                            ]);
+                        }
+                        fields.extend(filled_fields(exchange_rate, filled));
+                        lines.push(self.row(exchange_rate, resampled, &fields));
+                    }
+                }
+            }
+            Layout::Wide => {
+                let currencies = exchange_rate_results
+                    .iter()
+                    .flat_map(|exchange_rate| exchange_rate.rates.keys())
+                    .collect::<BTreeSet<&SmolStr>>();
+                if self.header {
+                    let columns = currencies.iter().map(|x| x.as_str());
+                    let columns = columns.chain(trailing_columns.iter().copied());
+                    lines.push(self.line(Self::leading_columns(resampled).chain(columns)));
+                }
+                for exchange_rate in exchange_rate_results {
+                    let rates = currencies
+                        .iter()
+                        .map(|currency| {
+                            exchange_rate
+                                .rates
+                                .get(*currency)
+                                .map(|rate| rate.to_string())
+                                .unwrap_or_default()
+                        })
+                        .chain(filled_fields(exchange_rate, filled))
+                        .collect::<Vec<_>>();
+                    lines.push(self.row(exchange_rate, resampled, &rates));
+                }
+            }
+            Layout::Matrix => {
+                let mut table = TableMatrix::from(exchange_rate_results);
+                table.sort(sort_by);
+                if self.header {
+                    let columns = table.get_columns().iter().map(|x| x.as_str());
+                    lines.push(self.line(["currency"].into_iter().chain(columns)));
+                }
+                for (currency, values) in table.get_rows() {
+                    let values = values
+                        .iter()
+                        .map(|value| value.map(|x| x.to_string()).unwrap_or_default())
+                        .collect::<Vec<_>>();
+                    let values = values.iter().map(String::as_str);
+                    lines.push(self.line([currency.as_str()].into_iter().chain(values)));
+                }
+                // Filled in days are marked by a row per column of the other layouts
+                if filled {
+                    for (i, label) in FILLED.into_iter().enumerate() {
+                        let values = exchange_rate_results
+                            .iter()
+                            .map(|x| filled_fields(x, filled).swap_remove(i))
+                            .collect::<Vec<_>>();
+                        let values = values.iter().map(String::as_str);
+                        lines.push(self.line([label].into_iter().chain(values)));
+                    }
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn render_conversion(&self, conversion: &Conversion) -> String {
+        let mut lines = Vec::with_capacity(conversion.to.len() + 1);
+        if self.header {
+            let columns = ["from", "amount", "currency", "rate", "converted"];
+            lines.push(self.line(["date"].into_iter().chain(columns)));
+        }
+        let amount = conversion.amount.to_string();
+        for converted in conversion.to.iter() {
+            let rate = converted.rate.to_string();
+            let converted_amount = converted.amount.to_string();
+            let fields = [
+                conversion.from.as_str(),
+                &amount,
+                &converted.currency,
+                &rate,
+                &converted_amount,
+            ];
+            lines.push(self.line([conversion.time.as_str()].into_iter().chain(fields)));
+        }
+        lines.join("\n")
+    }
+
+    pub fn render_stats(&self, stats: &[Stats]) -> String {
+        let mut lines = Vec::with_capacity(stats.len() + 1);
+        if self.header {
+            let columns = [
+                "currency",
+                "count",
+                "first_date",
+                "first",
+                "last_date",
+                "last",
+                "min_date",
+                "min",
+                "max_date",
+                "max",
+                "mean",
+                "median",
+                "stddev",
+                "change",
+                "change_percent",
+            ];
+            lines.push(self.line(columns));
+        }
+        for stats in stats {
+            let fields = [
+                stats.currency.to_string(),
+                stats.count.to_string(),
+                stats.first.time.to_string(),
+                stats.first.rate.to_string(),
+                stats.last.time.to_string(),
+                stats.last.rate.to_string(),
+                stats.min.time.to_string(),
+                stats.min.rate.to_string(),
+                stats.max.time.to_string(),
+                stats.max.rate.to_string(),
+                stats.mean.to_string(),
+                stats.median.to_string(),
+                stats.stddev.to_string(),
+                stats.change.to_string(),
+                stats.change_percent.to_string(),
+            ];
+            lines.push(self.line(fields.iter().map(String::as_str)));
+        }
+        lines.join("\n")
+    }
+
+    /// Renders the correlations as a matrix, with a row and a column per currency
+    pub fn render_correlations(&self, correlations: &[Correlation]) -> String {
+        let mut lines = Vec::with_capacity(correlations.len() + 1);
+        if self.header {
+            let currencies = correlations.iter().map(|x| x.currency.as_str());
+            lines.push(self.line(["currency"].into_iter().chain(currencies)));
+        }
+        for correlation in correlations {
+            let fields = correlations
+                .iter()
+                .map(|other| {
+                    let value = correlation.correlations.get(&other.currency).copied();
+                    value.flatten().map(|x| x.to_string()).unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+            let fields = [correlation.currency.as_str()]
+                .into_iter()
+                .chain(fields.iter().map(String::as_str));
+            lines.push(self.line(fields));
+        }
+        lines.join("\n")
+    }
+
+    /// The names of the columns before the rates
+    fn leading_columns<'a>(resampled: bool) -> impl Iterator<Item = &'a str> {
+        ["date"].into_iter().chain(resampled.then_some("period"))
+    }
+
+    /// A row of the rates, after the date and period of the publication
+    fn row(
+        &self,
+        exchange_rate: &ExchangeRateResult,
+        resampled: bool,
+        fields: &[String],
+    ) -> String {
+        let period = resampled.then(|| exchange_rate.period.as_deref().unwrap_or_default());
+        let fields = period.into_iter().chain(fields.iter().map(String::as_str));
+        self.line([exchange_rate.time.as_str()].into_iter().chain(fields))
+    }
+
+    /// Joins the fields into a single row, quoting them where needed
+    pub fn line<'a>(&self, fields: impl IntoIterator<Item = &'a str>) -> String {
+        fields
+            .into_iter()
+            .map(|field| self.quote(field))
+            .collect::<Vec<_>>()
+            .join(&self.delimiter.to_string())
+    }
+
+    /// Quotes the field if it contains anything that would break the row apart
+    fn quote(&self, field: &str) -> String {
+        let needs_quotes = field
+            .chars()
+            .any(|c| c == self.delimiter || c == '"' || c == '\n' || c == '\r');
+        if needs_quotes {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+/// The values of the [FILLED] columns, if there are any
+fn filled_fields(exchange_rate: &ExchangeRateResult, filled: bool) -> Vec<String> {
+    if !filled {
+        return Vec::new();
+    }
+    let reason = exchange_rate.reason.as_deref().unwrap_or_default();
+    vec![exchange_rate.filled.to_string(), reason.to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Aggregates, RateChange};
+    use crate::test_utils::exchange_rate_results;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_long_layout() {
+        let csv = Delimited::csv(Layout::Long).render(&exchange_rate_results(), &SortBy::Currency);
+        assert_eq!(
+            csv,
+            "date,currency,rate\n2025-01-07,SEK,11.5\n2025-01-07,USD,1.25\n2025-01-08,USD,1.5"
+        );
+    }
+
+    #[test]
+    fn test_wide_layout_without_header() {
+        let mut tsv = Delimited::tsv(Layout::Wide);
+        tsv.header = false;
+        let tsv = tsv.render(&exchange_rate_results(), &SortBy::Currency);
+        assert_eq!(tsv, "2025-01-07\t11.5\t1.25\n2025-01-08\t\t1.5");
+    }
+
+    #[test]
+    fn test_matrix_layout() {
+        let csv =
+            Delimited::csv(Layout::Matrix).render(&exchange_rate_results(), &SortBy::Currency);
+        assert_eq!(
+            csv,
+            "currency,2025-01-07,2025-01-08\nSEK,11.5,\nUSD,1.25,1.5"
+        );
+    }
+
+    #[test]
+    fn test_resampled_long_layout() {
+        let mut exchange_rate_results = exchange_rate_results();
+        exchange_rate_results.truncate(1);
+        exchange_rate_results[0].period = Some("2025-01".into());
+        exchange_rate_results[0].aggregates = HashMap::from([(
+            "USD".into(),
+            Aggregates {
+                mean: 1.25,
+                close: 1.5,
+                open: 1.0,
+                high: 1.5,
+                low: 1.0,
+            },
+        )]);
+        let csv = Delimited::csv(Layout::Long).render(&exchange_rate_results, &SortBy::Currency);
+        assert_eq!(
+            csv,
+            "date,period,currency,rate,mean,close,open,high,low\n\
+             2025-01-07,2025-01,SEK,11.5,,,,,\n\
+             2025-01-07,2025-01,USD,1.25,1.25,1.5,1,1.5,1"
+        );
+    }
+
+    #[test]
+    fn test_filled_wide_layout() {
+        let mut exchange_rate_results = exchange_rate_results();
+        exchange_rate_results[1].filled = true;
+        exchange_rate_results[1].reason = Some("weekend".into());
+        let csv = Delimited::csv(Layout::Wide).render(&exchange_rate_results, &SortBy::Currency);
+        assert_eq!(
+            csv,
+            "date,SEK,USD,filled,reason\n2025-01-07,11.5,1.25,false,\n2025-01-08,,1.5,true,weekend"
+        );
+    }
+
+    #[test]
+    fn test_changes_long_layout() {
+        let mut exchange_rate_results = exchange_rate_results();
+        let change = RateChange {
+            change: 0.25,
+            change_percent: 20.0,
+        };
+        exchange_rate_results[1]
+            .changes
+            .insert("USD".into(), change);
+        let csv = Delimited::csv(Layout::Long).render(&exchange_rate_results, &SortBy::Currency);
+        assert_eq!(
+            csv,
+            "date,currency,rate,change,change_percent\n2025-01-07,SEK,11.5,,\n\
+             2025-01-07,USD,1.25,,\n2025-01-08,USD,1.5,0.25,20"
+        );
+    }
+
+    #[test]
+    fn test_filled_matrix_layout() {
+        let mut exchange_rate_results = exchange_rate_results();
+        exchange_rate_results[1].filled = true;
+        exchange_rate_results[1].reason = Some("weekend".into());
+        let csv = Delimited::csv(Layout::Matrix).render(&exchange_rate_results, &SortBy::Currency);
+        assert_eq!(
+            csv,
+            "currency,2025-01-07,2025-01-08\nSEK,11.5,\nUSD,1.25,1.5\nfilled,false,true\nreason,,weekend"
+        );
+    }
+
+    #[test]
+    fn test_quoting() {
+        let csv = Delimited::csv(Layout::Long);
+        assert_eq!(csv.quote("USD"), "USD");
+        assert_eq!(csv.quote("a,b"), "\"a,b\"");
+        assert_eq!(csv.quote("a\"b"), "\"a\"\"b\"");
+        assert_eq!(Delimited::tsv(Layout::Long).quote("a,b"), "a,b");
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt::Write as _;
+
+use crate::HeaderDescription;
+use crate::cli::SortBy;
+use crate::models::ExchangeRateResult;
+use crate::table::TableMatrix;
+use crate::utils_calc::round_value;
+
+use super::svg::escape;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0.2em; }
+.window { color: #666; margin-top: 0; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; }
+th { text-align: left; background: #f4f4f4; }
+td.rate { text-align: right; font-variant-numeric: tabular-nums; }
+.up { color: #1a7f37; }
+.down { color: #cf222e; }
+.days { display: flex; flex-wrap: wrap; gap: 0 2em; }
+.day h3 { margin-bottom: 0.3em; }
+.filled { color: #666; font-weight: normal; font-size: 0.8em; }
+svg.sparkline { vertical-align: middle; }";
+
+/// Renders a standalone HTML page, with a summary of the changes over the window, and a table
+/// per day. It doesn't depend on any external assets, so it can be emailed as is.
+pub struct Html {
+    /// Whether to show the dates
+    pub time: bool,
+    /// Decimals to round the changes to
+    pub max_decimals: u8,
+}
+
+impl Html {
+    pub fn new(max_decimals: u8) -> Self {
+        Self {
+            time: true,
+            max_decimals,
+        }
+    }
+
+    pub fn render(
+        &self,
+        exchange_rate_results: &[ExchangeRateResult],
+        header_description: &HeaderDescription,
+        sort_by: &SortBy,
+    ) -> String {
+        let mut exchange_rate_results = exchange_rate_results.to_vec();
+        exchange_rate_results.sort_by(|a, b| a.time.cmp(&b.time));
+
+        let mut title = header_description.plain();
+        if header_description.is_inverted() {
+            title.push_str(" (inverted)");
+        }
+
+        let mut html = String::new();
+        let _ = writeln!(html, "<!DOCTYPE html>");
+        let _ = writeln!(html, "<html lang=\"en\">");
+        let _ = writeln!(html, "<head>");
+        let _ = writeln!(html, "<meta charset=\"utf-8\">");
+        let _ = writeln!(html, "<title>{}</title>", escape(&title));
+        let _ = writeln!(html, "<style>\n{}\n</style>", STYLE);
+        let _ = writeln!(html, "</head>");
+        let _ = writeln!(html, "<body>");
+        let _ = writeln!(html, "<h1>{}</h1>", escape(&title));
+        if let (Some(first), Some(last), true) = (
+            exchange_rate_results.first(),
+            exchange_rate_results.last(),
+            self.time,
+        ) {
+            let _ = writeln!(
+                html,
+                "<p class=\"window\">{} to {}, {} publications</p>",
+                escape(first.label()),
+                escape(last.label()),
+                exchange_rate_results.len()
+            );
+        }
+
+        self.write_summary(&mut html, &exchange_rate_results, sort_by);
+        self.write_days(&mut html, &exchange_rate_results, sort_by);
+
+        let _ = writeln!(html, "</body>");
+        html.push_str("</html>");
+        html
+    }
+
+    /// A row per currency, with its trend and its change from the first to the last day
+    fn write_summary(
+        &self,
+        html: &mut String,
+        exchange_rate_results: &[ExchangeRateResult],
+        sort_by: &SortBy,
+    ) {
+        let mut table = TableMatrix::from(exchange_rate_results);
+        table.sort(sort_by);
+
+        let _ = writeln!(html, "<h2>Changes</h2>");
+        let _ = writeln!(html, "<table class=\"summary\">");
+        let _ = writeln!(
+            html,
+            "<tr><th>Currency</th><th>Trend</th><th>First</th><th>Last</th><th>Change</th><th>Change %</th></tr>"
+        );
+        for (currency, values) in table.get_rows() {
+            let values = values.iter().flatten().copied().collect::<Vec<_>>();
+            let (Some(first), Some(last)) = (values.first(), values.last()) else {
+                continue;
+            };
+            let change = round_value(last - first, self.max_decimals);
+            let class = match change {
+                change if change > 0.0 => "rate up",
+                change if change < 0.0 => "rate down",
+                _ => "rate",
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"rate\">{}</td><td class=\"rate\">{}</td>\
+                 <td class=\"{class}\">{:+}</td><td class=\"{class}\">{:+.2}%</td></tr>",
+                escape(currency),
+                sparkline(&values),
+                first,
+                last,
+                change,
+                (last / first - 1.0) * 100.0,
+            );
+        }
+        let _ = writeln!(html, "</table>");
+    }
+
+    /// A table per day, the latest first. Filled in days are marked next to their date.
+    fn write_days(
+        &self,
+        html: &mut String,
+        exchange_rate_results: &[ExchangeRateResult],
+        sort_by: &SortBy,
+    ) {
+        let comparer = sort_by.get_comparer();
+        let _ = writeln!(html, "<h2>Rates per day</h2>");
+        let _ = writeln!(html, "<div class=\"days\">");
+        for exchange_rate in exchange_rate_results.iter().rev() {
+            let _ = writeln!(html, "<section class=\"day\">");
+            if self.time {
+                let _ = write!(html, "<h3>{}", escape(exchange_rate.label()));
+                if let Some(reason) = exchange_rate
+                    .reason
+                    .as_ref()
+                    .filter(|_| exchange_rate.filled)
+                {
+                    let note = format!("filled, not published: {}", reason);
+                    let _ = write!(html, " <span class=\"filled\">{}</span>", escape(&note));
+                }
+                let _ = writeln!(html, "</h3>");
+            } else if exchange_rate.filled {
+                let _ = writeln!(html, "<p class=\"filled\">filled, not published</p>");
+            }
+            let _ = writeln!(html, "<table>");
+            let _ = writeln!(html, "<tr><th>Currency</th><th>Rate</th></tr>");
+            let mut rates = exchange_rate
+                .rates
+                .iter()
+                .map(|(currency, rate)| (currency.as_str(), *rate))
+                .collect::<Vec<_>>();
+            rates.sort_by(comparer);
+            for (currency, rate) in rates {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td class=\"rate\">{}</td></tr>",
+                    escape(currency),
+                    rate
+                );
+            }
+            let _ = writeln!(html, "</table>");
+            let _ = writeln!(html, "</section>");
+        }
+        let _ = writeln!(html, "</div>");
+    }
+}
+
+/// A small inline SVG of the values
+fn sparkline(values: &[f64]) -> String {
+    const WIDTH: f64 = 120.0;
+    const HEIGHT: f64 = 24.0;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let steps = values.len().saturating_sub(1).max(1) as f64;
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let x = i as f64 / steps * WIDTH;
+            let y = if max > min {
+                HEIGHT - 2.0 - (value - min) / (max - min) * (HEIGHT - 4.0)
+            } else {
+                HEIGHT / 2.0
+            };
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "<svg class=\"sparkline\" width=\"{WIDTH}\" height=\"{HEIGHT}\" viewBox=\"0 0 {WIDTH} {HEIGHT}\">\
+         <polyline points=\"{}\" fill=\"none\" stroke=\"#4e79a7\" stroke-width=\"1.5\"/></svg>",
+        points
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_summary() {
+        let exchange_rate_results = [
+            ExchangeRateResult::new("2025-01-08".into(), HashMap::from([("USD".into(), 1.5)])),
+            ExchangeRateResult::new("2025-01-07".into(), HashMap::from([("USD".into(), 1.25)])),
+        ];
+        let html = Html::new(5).render(
+            &exchange_rate_results,
+            &HeaderDescription::new(),
+            &SortBy::Currency,
+        );
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</html>"));
+        assert!(html.contains("2025-01-07 to 2025-01-08, 2 publications"));
+        assert!(
+            html.contains("<td class=\"rate up\">+0.25</td><td class=\"rate up\">+20.00%</td>")
+        );
+        assert!(html.contains("<polyline points=\"0.0,22.0 120.0,2.0\""));
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Renders anything serializable as JSON. Without `time`, the "time" key is removed from every
+/// object at the top level, or in a top level array.
+pub fn render<T: Serialize + ?Sized>(value: &T, time: bool, compact: bool) -> String {
+    let json_value = to_value(value, time);
+
+    let to_string_json = if compact {
+        serde_json::to_string
+    } else {
+        serde_json::to_string_pretty
+    };
+    to_string_json(&json_value).expect("Failed to parse content as JSON")
+}
+
+/// The structure of the JSON output, which the other structured formats share. The keys of every
+/// object are sorted.
+pub fn to_value<T: Serialize + ?Sized>(value: &T, time: bool) -> Value {
+    let mut json_value =
+        serde_json::to_value(value).expect("Failed to parse content as JSON value");
+
+    if !time {
+        let objects = match &mut json_value {
+            Value::Array(values) => values.iter_mut().collect(),
+            value => vec![value],
+        };
+        objects
+            .into_iter()
+            .filter_map(|json_value| json_value.as_object_mut())
+            .for_each(|map| {
+                map.remove_entry("time");
+            });
+    }
+
+    json_value
+}
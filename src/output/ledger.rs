@@ -0,0 +1,130 @@
+use crate::HeaderDescription;
+use crate::cli::SortBy;
+use crate::models::{Conversion, ExchangeRateResult};
+
+/// One unit of `commodity` costs `rate` of `currency` on `time`
+pub(super) struct Price<'a> {
+    pub time: &'a str,
+    pub commodity: &'a str,
+    pub rate: f64,
+    pub currency: &'a str,
+}
+
+/// Lists the prices in chronological order, so they can be appended to a journal.
+/// Filled in days are left out, since nothing was published on them.
+pub(super) fn prices<'a>(
+    exchange_rate_results: &'a [ExchangeRateResult],
+    header_description: &HeaderDescription<'a>,
+    sort_by: &SortBy,
+) -> Vec<Price<'a>> {
+    let base = header_description.base();
+    let comparer = sort_by.get_comparer();
+
+    let mut exchange_rate_results = exchange_rate_results
+        .iter()
+        .filter(|exchange_rate| !exchange_rate.filled)
+        .collect::<Vec<_>>();
+    exchange_rate_results.sort_by(|a, b| a.time.cmp(&b.time));
+
+    let mut prices = Vec::new();
+    for exchange_rate in exchange_rate_results {
+        let mut rates = exchange_rate
+            .rates
+            .iter()
+            .map(|(currency, rate)| (currency.as_str(), *rate))
+            .collect::<Vec<_>>();
+        rates.sort_by(comparer);
+        for (currency, rate) in rates {
+            // Inverted rates are the price of the currency, rather than of the base
+            let (commodity, currency) = if header_description.is_inverted() {
+                (currency, base)
+            } else {
+                (base, currency)
+            };
+            prices.push(Price {
+                time: &exchange_rate.time,
+                commodity,
+                rate,
+                currency,
+            });
+        }
+    }
+    prices
+}
+
+/// Renders the rates as ledger/hledger price directives, e.g. `P 2024-05-02 EUR 1.0729 USD`
+pub fn render(
+    exchange_rate_results: &[ExchangeRateResult],
+    header_description: &HeaderDescription,
+    sort_by: &SortBy,
+) -> String {
+    prices(exchange_rate_results, header_description, sort_by)
+        .into_iter()
+        .map(|price| directive(&price))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(super) fn conversion_prices(conversion: &Conversion) -> Vec<Price<'_>> {
+    conversion
+        .to
+        .iter()
+        .map(|converted| Price {
+            time: &conversion.time,
+            commodity: &conversion.from,
+            rate: converted.rate,
+            currency: &converted.currency,
+        })
+        .collect()
+}
+
+pub fn render_conversion(conversion: &Conversion) -> String {
+    conversion_prices(conversion)
+        .iter()
+        .map(directive)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn directive(price: &Price) -> String {
+    format!(
+        "P {} {} {} {}",
+        price.time, price.commodity, price.rate, price.currency
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_directives() {
+        let exchange_rate_results = [
+            ExchangeRateResult::new("2024-05-03".into(), HashMap::from([("USD".into(), 0.9301)])),
+            ExchangeRateResult::new(
+                "2024-05-02".into(),
+                HashMap::from([("USD".into(), 0.9321), ("SEK".into(), 0.0855)]),
+            ),
+        ];
+        let mut header_description = HeaderDescription::new();
+        assert_eq!(
+            render(
+                &exchange_rate_results[..1],
+                &header_description,
+                &SortBy::Currency
+            ),
+            "P 2024-05-03 EUR 0.9301 USD"
+        );
+
+        header_description.invert();
+        assert_eq!(
+            render(
+                &exchange_rate_results,
+                &header_description,
+                &SortBy::Currency
+            ),
+            "P 2024-05-02 SEK 0.0855 EUR\nP 2024-05-02 USD 0.9321 EUR\nP 2024-05-03 USD 0.9301 EUR"
+        );
+    }
+}
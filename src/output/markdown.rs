@@ -0,0 +1,259 @@
+use std::collections::BTreeSet;
+
+use smol_str::SmolStr;
+
+use crate::HeaderDescription;
+use crate::cli::{Layout, SortBy};
+use crate::models::{Conversion, ExchangeRateResult};
+use crate::table::TableMatrix;
+
+/// Renders rates as GitHub flavoured markdown tables, below a title line
+pub struct Markdown {
+    pub layout: Layout,
+    /// Whether to include the dates
+    pub time: bool,
+}
+
+impl Markdown {
+    pub fn new(layout: Layout) -> Self {
+        Self { layout, time: true }
+    }
+
+    pub fn render(
+        &self,
+        exchange_rate_results: &[ExchangeRateResult],
+        header_description: &HeaderDescription,
+        sort_by: &SortBy,
+    ) -> String {
+        let mut blocks = vec![title(header_description)];
+        match self.layout {
+            Layout::Long => {
+                let comparer = sort_by.get_comparer();
+                for exchange_rate in exchange_rate_results {
+                    if self.time {
+                        let mut title = format!("**{}**", exchange_rate.label());
+                        if let Some(note) = filled_note(exchange_rate) {
+                            title.push_str(&format!(" *({})*", note));
+                        }
+                        blocks.push(title);
+                    }
+                    let mut rates = exchange_rate
+                        .rates
+                        .iter()
+                        .map(|(currency, rate)| (currency.as_str(), *rate))
+                        .collect::<Vec<_>>();
+                    rates.sort_by(comparer);
+                    let changed = !exchange_rate.changes.is_empty();
+                    let rows = rates
+                        .into_iter()
+                        .map(|(currency, rate)| {
+                            let mut row = vec![currency.to_string(), rate.to_string()];
+                            if changed {
+                                let change = exchange_rate.changes.get(currency);
+                                row.extend([
+                                    change
+                                        .map(|x| format!("{:+}", x.change))
+                                        .unwrap_or_default(),
+                                    change
+                                        .map(|x| format!("{:+}%", x.change_percent))
+                                        .unwrap_or_default(),
+                                ]);
+                            }
+                            row
+                        })
+                        .collect();
+                    let columns = ["Currency", "Rate", "Change", "Change %"];
+                    let columns = if changed { &columns[..] } else { &columns[..2] };
+                    blocks.push(table(columns, 1, rows));
+                }
+            }
+            Layout::Wide => {
+                let currencies = exchange_rate_results
+                    .iter()
+                    .flat_map(|exchange_rate| exchange_rate.rates.keys())
+                    .collect::<BTreeSet<&SmolStr>>();
+                let filled = exchange_rate_results.iter().any(|x| x.filled);
+                let dates = self.time.then_some("Date");
+                let columns = dates
+                    .into_iter()
+                    .chain(currencies.iter().map(|x| x.as_str()))
+                    .chain(filled.then_some("Note"))
+                    .collect::<Vec<_>>();
+                let rows = exchange_rate_results
+                    .iter()
+                    .map(|exchange_rate| {
+                        let rates = currencies.iter().map(|currency| {
+                            exchange_rate
+                                .rates
+                                .get(*currency)
+                                .map(|rate| rate.to_string())
+                                .unwrap_or_default()
+                        });
+                        let time = self.time.then(|| exchange_rate.label().to_string());
+                        let note = filled.then(|| filled_note(exchange_rate).unwrap_or_default());
+                        time.into_iter().chain(rates).chain(note).collect()
+                    })
+                    .collect();
+                blocks.push(table(&columns, usize::from(self.time), rows));
+            }
+            Layout::Matrix => {
+                let mut matrix = TableMatrix::from(exchange_rate_results);
+                matrix.sort(sort_by);
+                let columns = [matrix.get_corner()]
+                    .into_iter()
+                    .chain(matrix.get_columns().iter().map(|x| x.as_str()))
+                    .collect::<Vec<_>>();
+                let rows = matrix
+                    .get_rows()
+                    .iter()
+                    .map(|(currency, values)| {
+                        let values = values
+                            .iter()
+                            .map(|value| value.map(|x| x.to_string()).unwrap_or_default());
+                        [currency.to_string()].into_iter().chain(values).collect()
+                    })
+                    .collect();
+                blocks.push(table(&columns, 1, rows));
+                let notes = exchange_rate_results
+                    .iter()
+                    .filter_map(|x| Some(format!("{} {}", x.label(), filled_note(x)?)))
+                    .collect::<Vec<_>>();
+                if !notes.is_empty() {
+                    blocks.push(format!("*{}*", notes.join(", ")));
+                }
+            }
+        }
+
+        blocks.join("\n\n")
+    }
+
+    pub fn render_conversion(&self, conversion: &Conversion) -> String {
+        let mut title = format!("### {} {}", conversion.amount, conversion.from);
+        if self.time {
+            title.push_str(&format!(" on {}", conversion.time));
+        }
+        let rows = conversion
+            .to
+            .iter()
+            .map(|converted| {
+                vec![
+                    converted.currency.to_string(),
+                    converted.rate.to_string(),
+                    converted.amount.to_string(),
+                ]
+            })
+            .collect();
+        let table = table(&["Currency", "Rate", "Amount"], 1, rows);
+        format!("{}\n\n{}", title, table)
+    }
+}
+
+/// Marks a filled in day, e.g. `filled, not published: weekend`
+fn filled_note(exchange_rate: &ExchangeRateResult) -> Option<String> {
+    let reason = exchange_rate
+        .reason
+        .as_deref()
+        .filter(|_| exchange_rate.filled)?;
+    Some(format!("filled, not published: {}", reason))
+}
+
+fn title(header_description: &HeaderDescription) -> String {
+    let mut title = format!("### {}", header_description.plain());
+    if header_description.is_inverted() {
+        title.push_str(" (inverted)");
+    }
+    title
+}
+
+/// Lays out a table with padded cells. The first `left_aligned` columns are aligned to the left,
+/// the rest (the rates) to the right.
+fn table(columns: &[&str], left_aligned: usize, rows: Vec<Vec<String>>) -> String {
+    let widths = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain([column.len(), 3])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let pad = |i: usize, cell: &str| {
+        if i < left_aligned {
+            format!("{:<width$}", cell, width = widths[i])
+        } else {
+            format!("{:>width$}", cell, width = widths[i])
+        }
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    lines.push(line(
+        columns.iter().enumerate().map(|(i, x)| pad(i, x)).collect(),
+    ));
+    lines.push(line(
+        widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| {
+                let dashes = "-".repeat(width - 1);
+                if i < left_aligned {
+                    format!(":{}", dashes)
+                } else {
+                    format!("{}:", dashes)
+                }
+            })
+            .collect(),
+    ));
+    for row in rows {
+        lines.push(line(
+            row.iter().enumerate().map(|(i, x)| pad(i, x)).collect(),
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::exchange_rate_results;
+
+    #[test]
+    fn test_table_per_day() {
+        let markdown = Markdown::new(Layout::Long).render(
+            &exchange_rate_results()[1..],
+            &HeaderDescription::new(),
+            &SortBy::Currency,
+        );
+        assert_eq!(
+            markdown,
+            "### EUR to ALL\n\n\
+             **2025-01-08**\n\n\
+             | Currency | Rate |\n\
+             | :------- | ---: |\n\
+             | USD      |  1.5 |"
+        );
+    }
+
+    #[test]
+    fn test_pivoted_and_inverted() {
+        let mut header_description = HeaderDescription::new();
+        header_description.replace_eur("USD");
+        header_description.invert();
+        let markdown = Markdown::new(Layout::Wide).render(
+            &exchange_rate_results(),
+            &header_description,
+            &SortBy::Currency,
+        );
+        assert_eq!(
+            markdown,
+            "### ALL to USD (inverted)\n\n\
+             | Date       |  SEK |  USD |\n\
+             | :--------- | ---: | ---: |\n\
+             | 2025-01-07 | 11.5 | 1.25 |\n\
+             | 2025-01-08 |      |  1.5 |"
+        );
+    }
+}
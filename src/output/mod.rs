@@ -0,0 +1,21 @@
+mod beancount;
+mod chart;
+mod delimited;
+mod html;
+pub mod json;
+pub mod ledger;
+mod markdown;
+pub mod ndjson;
+pub mod plain;
+mod prometheus;
+mod svg;
+pub mod toml;
+pub mod yaml;
+
+pub use beancount::Beancount;
+pub use chart::Chart;
+pub use delimited::Delimited;
+pub use html::Html;
+pub use markdown::Markdown;
+pub use prometheus::Prometheus;
+pub use svg::SvgChart;
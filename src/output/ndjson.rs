@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::cli::{Layout, SortBy};
+use crate::models::{Aggregates, ExchangeRateResult, RateChange};
+
+#[derive(Serialize)]
+struct Day<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<&'a str>,
+    rates: BTreeMap<&'a str, f64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    aggregates: BTreeMap<&'a str, &'a Aggregates>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    changes: BTreeMap<&'a str, &'a RateChange>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    filled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct Rate<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<&'a str>,
+    currency: &'a str,
+    rate: f64,
+    #[serde(flatten)]
+    aggregates: Option<&'a Aggregates>,
+    #[serde(flatten)]
+    change: Option<&'a RateChange>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    filled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'a str>,
+}
+
+/// Writes the rates as newline delimited JSON, one record per line. The long layout writes a
+/// record per date and currency, the wide layout a record per date. A stream can't be pivoted, so
+/// the matrix layout is written like the wide one.
+pub fn write<W: Write>(
+    writer: &mut W,
+    exchange_rate_results: &[ExchangeRateResult],
+    layout: Layout,
+    time: bool,
+    sort_by: &SortBy,
+) -> io::Result<()> {
+    for exchange_rate in exchange_rate_results {
+        write_day(writer, exchange_rate, layout, time, sort_by)?;
+    }
+    writer.flush()
+}
+
+/// Writes the records of a single day, so the days can be streamed as they're read
+pub fn write_day<W: Write>(
+    writer: &mut W,
+    exchange_rate: &ExchangeRateResult,
+    layout: Layout,
+    time: bool,
+    sort_by: &SortBy,
+) -> io::Result<()> {
+    let time = time.then_some(exchange_rate.time.as_str());
+    match layout {
+        Layout::Long => {
+            let mut rates = exchange_rate
+                .rates
+                .iter()
+                .map(|(currency, rate)| (currency.as_str(), *rate))
+                .collect::<Vec<_>>();
+            rates.sort_by(sort_by.get_comparer());
+            for (currency, rate) in rates {
+                record(
+                    writer,
+                    &Rate {
+                        time,
+                        period: exchange_rate.period.as_deref(),
+                        currency,
+                        rate,
+                        aggregates: exchange_rate.aggregates.get(currency),
+                        change: exchange_rate.changes.get(currency),
+                        filled: exchange_rate.filled,
+                        reason: exchange_rate.reason.as_deref(),
+                    },
+                )?;
+            }
+        }
+        Layout::Wide | Layout::Matrix => {
+            let rates = exchange_rate
+                .rates
+                .iter()
+                .map(|(currency, rate)| (currency.as_str(), *rate))
+                .collect();
+            record(
+                writer,
+                &Day {
+                    time,
+                    period: exchange_rate.period.as_deref(),
+                    rates,
+                    aggregates: exchange_rate
+                        .aggregates
+                        .iter()
+                        .map(|(currency, aggregates)| (currency.as_str(), aggregates))
+                        .collect(),
+                    changes: exchange_rate
+                        .changes
+                        .iter()
+                        .map(|(currency, change)| (currency.as_str(), change))
+                        .collect(),
+                    filled: exchange_rate.filled,
+                    reason: exchange_rate.reason.as_deref(),
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn record<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::exchange_rate_results;
+
+    #[test]
+    fn test_long_layout() {
+        let mut buffer = Vec::new();
+        write(
+            &mut buffer,
+            &exchange_rate_results(),
+            Layout::Long,
+            true,
+            &SortBy::Currency,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"time\":\"2025-01-07\",\"currency\":\"SEK\",\"rate\":11.5}\n\
+             {\"time\":\"2025-01-07\",\"currency\":\"USD\",\"rate\":1.25}\n\
+             {\"time\":\"2025-01-08\",\"currency\":\"USD\",\"rate\":1.5}\n"
+        );
+    }
+
+    #[test]
+    fn test_wide_layout_without_time() {
+        let mut buffer = Vec::new();
+        write(
+            &mut buffer,
+            &exchange_rate_results(),
+            Layout::Wide,
+            false,
+            &SortBy::Currency,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"rates\":{\"SEK\":11.5,\"USD\":1.25}}\n{\"rates\":{\"USD\":1.5}}\n"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use colored::Colorize;
+use smol_str::format_smolstr;
+
+use crate::HeaderDescription;
+use crate::cli::{Layout, SortBy};
+use crate::models::{Conversion, Correlation, ExchangeRateResult, Stats};
+use crate::table::{Table, TableMatrix, TableRef, TableTrait as _};
+
+/// Renders one table per publication, or a single pivoted table, below the header description
+pub fn render(
+    exchange_rate_results: &[ExchangeRateResult],
+    header_description: &HeaderDescription,
+    time: bool,
+    sort_by: &SortBy,
+    layout: Layout,
+) -> String {
+    if layout != Layout::Long {
+        let mut table = TableMatrix::from(exchange_rate_results);
+        table.sort(sort_by);
+        if layout == Layout::Wide {
+            table.transpose("Date".into());
+        }
+        let filled = exchange_rate_results
+            .iter()
+            .filter(|x| x.filled)
+            .map(|x| format!("{} {}", x.label(), x.reason.as_deref().unwrap_or_default()))
+            .collect::<Vec<_>>();
+        if filled.is_empty() {
+            return format!("{}{}", header_description, table);
+        }
+        let note = format!("Filled, not published: {}", filled.join(", "));
+        return format!("{}{} {}\n", header_description, table, note.dimmed());
+    }
+
+    let rates = exchange_rate_results
+        .iter()
+        .map(|exchange_rate| {
+            let mut t: TableRef = exchange_rate.into();
+            if !time {
+                t.disable_header();
+            }
+            t.sort(sort_by);
+            match exchange_rate.reason.as_ref() {
+                Some(reason) if exchange_rate.filled => {
+                    format!(
+                        "{} {}\n",
+                        t,
+                        format!("Filled, not published: {}", reason).dimmed()
+                    )
+                }
+                _ => t.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut s = header_description.to_string();
+    s.push_str(&rates);
+    s
+}
+
+pub fn render_conversion(conversion: &Conversion, time: bool) -> String {
+    let mut lines = Vec::with_capacity(conversion.to.len() + 1);
+    if time {
+        lines.push(format!(" {}", conversion.time.bold().cyan()));
+    }
+    for converted in conversion.to.iter() {
+        lines.push(format!(
+            " {} {} = {} {}  ({} {} = {} {})",
+            conversion.amount,
+            conversion.from.bold().green(),
+            converted.amount.to_string().bold(),
+            converted.currency.bold().green(),
+            1,
+            conversion.from,
+            converted.rate,
+            converted.currency,
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Renders a table of statistics per currency, below the header description
+pub fn render_stats(stats: &[Stats], header_description: &HeaderDescription) -> String {
+    let tables = stats
+        .iter()
+        .map(|stats| {
+            let mut t = Table::new(
+                Some(stats.currency.clone()),
+                "Statistic".into(),
+                "Value".into(),
+            );
+            t.width = 32;
+            t.add_row(
+                format_smolstr!("First ({})", stats.first.time),
+                stats.first.rate,
+            );
+            t.add_row(
+                format_smolstr!("Last ({})", stats.last.time),
+                stats.last.rate,
+            );
+            t.add_row(format_smolstr!("Min ({})", stats.min.time), stats.min.rate);
+            t.add_row(format_smolstr!("Max ({})", stats.max.time), stats.max.rate);
+            t.add_row("Mean".into(), stats.mean);
+            t.add_row("Median".into(), stats.median);
+            t.add_row("Std dev".into(), stats.stddev);
+            t.add_row("Change".into(), stats.change);
+            t.add_row("Change %".into(), stats.change_percent);
+            t.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut s = header_description.to_string();
+    s.push_str(&tables);
+    s
+}
+
+/// Renders the correlations as a matrix, below the header description
+pub fn render_correlations(
+    correlations: &[Correlation],
+    header_description: &HeaderDescription,
+) -> String {
+    let currencies = correlations.iter().map(|x| x.currency.clone());
+    let mut table = TableMatrix::new("Currency".into(), currencies.collect());
+    for correlation in correlations {
+        let values = correlations
+            .iter()
+            .map(|other| {
+                let value = correlation.correlations.get(&other.currency).copied();
+                value.flatten()
+            })
+            .collect();
+        table.add_row(correlation.currency.clone(), values);
+    }
+    format!("{}{}", header_description, table)
+}
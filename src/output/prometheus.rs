@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+
+use crate::cli::SortBy;
+use crate::models::ExchangeRateResult;
+use crate::{Frankfurt, HeaderDescription};
+
+use super::ledger::{Price, prices};
+
+const METRIC: &str = "ecb_exchange_rate";
+const PUBLISHED_METRIC: &str = "ecb_exchange_rate_published_timestamp_seconds";
+
+/// Renders the latest rate of every currency as a Prometheus gauge,
+/// e.g. `ecb_exchange_rate{base="EUR",quote="USD"} 1.0812`.
+/// The series never change labels, so they can be scraped over time, e.g. by the textfile
+/// collector of node_exporter.
+pub struct Prometheus {
+    /// Whether to add a gauge of the publication time of every rate
+    pub time: bool,
+}
+
+impl Default for Prometheus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Prometheus {
+    pub fn new() -> Self {
+        Self { time: true }
+    }
+
+    pub fn render(
+        &self,
+        exchange_rate_results: &[ExchangeRateResult],
+        header_description: &HeaderDescription,
+        sort_by: &SortBy,
+    ) -> String {
+        // Only the latest price of each series, in the order of the latest publication
+        let prices = prices(exchange_rate_results, header_description, sort_by);
+        let mut seen = HashSet::new();
+        let mut latest = prices
+            .iter()
+            .rev()
+            .filter(|price| seen.insert((price.commodity, price.currency)))
+            .collect::<Vec<_>>();
+        latest.reverse();
+
+        let mut lines = vec![
+            format!(
+                "# HELP {} Euro foreign exchange reference rate from the ECB, in quote per base",
+                METRIC
+            ),
+            format!("# TYPE {} gauge", METRIC),
+        ];
+        for price in latest.iter() {
+            lines.push(format!("{}{{{}}} {}", METRIC, labels(price), price.rate));
+        }
+
+        if self.time {
+            lines.push(format!(
+                "# HELP {} Publication time of the latest rate from the ECB",
+                PUBLISHED_METRIC
+            ));
+            lines.push(format!("# TYPE {} gauge", PUBLISHED_METRIC));
+            for price in latest.iter() {
+                // Only a date has a publication time
+                if let Ok(date) = price.time.parse::<NaiveDate>() {
+                    let published = Frankfurt::publication_time(date).timestamp();
+                    lines.push(format!(
+                        "{}{{{}}} {}",
+                        PUBLISHED_METRIC,
+                        labels(price),
+                        published
+                    ));
+                }
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+fn labels(price: &Price) -> String {
+    [("base", price.commodity), ("quote", price.currency)]
+        .into_iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_gauges() {
+        let exchange_rate_results = [
+            ExchangeRateResult::new(
+                "2025-01-07".into(),
+                HashMap::from([("USD".into(), 1.0812), ("SEK".into(), 11.5)]),
+            ),
+            ExchangeRateResult::new("2025-01-08".into(), HashMap::from([("USD".into(), 1.09)])),
+        ];
+        let header_description = HeaderDescription::new();
+
+        let mut prometheus = Prometheus::new();
+        let rendered = prometheus.render(
+            &exchange_rate_results,
+            &header_description,
+            &SortBy::Currency,
+        );
+        // 16:00 CET
+        assert_eq!(
+            rendered
+                .lines()
+                .filter(|x| !x.starts_with('#'))
+                .collect::<Vec<_>>(),
+            [
+                "ecb_exchange_rate{base=\"EUR\",quote=\"SEK\"} 11.5",
+                "ecb_exchange_rate{base=\"EUR\",quote=\"USD\"} 1.09",
+                "ecb_exchange_rate_published_timestamp_seconds{base=\"EUR\",quote=\"SEK\"} 1736262000",
+                "ecb_exchange_rate_published_timestamp_seconds{base=\"EUR\",quote=\"USD\"} 1736348400",
+            ]
+        );
+
+        prometheus.time = false;
+        let rendered = prometheus.render(
+            &exchange_rate_results,
+            &header_description,
+            &SortBy::Currency,
+        );
+        assert_eq!(rendered.lines().count(), 4);
+    }
+}
@@ -0,0 +1,363 @@
+use std::fmt::Write as _;
+
+use chrono::NaiveDate;
+
+use crate::HeaderDescription;
+use crate::cli::SortBy;
+use crate::models::ExchangeRateResult;
+use crate::table::TableMatrix;
+
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 70.0;
+const LEGEND_ROW_HEIGHT: f64 = 18.0;
+
+/// Plots the rate history of one or more currencies as a standalone SVG image, with date and rate
+/// axes and a legend. Dates are spaced by the days between them, and the legend wraps onto as many
+/// rows as needed, which makes the image taller than `height`.
+pub struct SvgChart {
+    pub width: u32,
+    pub height: u32,
+    /// Rebases every currency to an index of 100 at its first date
+    pub rebase: bool,
+}
+
+impl Default for SvgChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvgChart {
+    pub fn new() -> Self {
+        Self {
+            width: 800,
+            height: 450,
+            rebase: false,
+        }
+    }
+
+    pub fn render(
+        &self,
+        exchange_rate_results: &[ExchangeRateResult],
+        header_description: &HeaderDescription,
+        sort_by: &SortBy,
+    ) -> String {
+        let mut exchange_rate_results = exchange_rate_results.to_vec();
+        exchange_rate_results.sort_by(|a, b| a.time.cmp(&b.time));
+        let mut table = TableMatrix::from(&exchange_rate_results[..]);
+        table.sort(sort_by);
+
+        let dates = table.get_columns();
+        let series = table
+            .get_rows()
+            .iter()
+            .map(|(currency, values)| {
+                let first = values.iter().flatten().next().copied();
+                let values = values
+                    .iter()
+                    .map(|value| match (value, first) {
+                        (Some(value), Some(first)) if self.rebase => Some(value / first * 100.0),
+                        (value, _) => *value,
+                    })
+                    .collect::<Vec<_>>();
+                (currency.as_str(), values)
+            })
+            .collect::<Vec<_>>();
+
+        let (width, height) = (self.width as f64, self.height as f64);
+        let plot_width = width - MARGIN_LEFT - MARGIN_RIGHT;
+        let plot_height = height - MARGIN_TOP - MARGIN_BOTTOM;
+
+        let values = series
+            .iter()
+            .flat_map(|(_, values)| values.iter().flatten());
+        let min = values.clone().copied().fold(f64::INFINITY, f64::min);
+        let max = values.copied().fold(f64::NEG_INFINITY, f64::max);
+        let (min, max) = if min > max {
+            (0.0, 1.0)
+        } else if min == max {
+            (min - 1.0, max + 1.0)
+        } else {
+            (min, max)
+        };
+        let ticks = nice_ticks(min, max, 5);
+        let (low, high) = (ticks[0], ticks[ticks.len() - 1]);
+
+        let positions = positions(&exchange_rate_results);
+        let span = positions.last().copied().unwrap_or_default();
+        let x = |i: usize| match span {
+            0.0 => MARGIN_LEFT + plot_width / 2.0,
+            span => MARGIN_LEFT + positions[i] / span * plot_width,
+        };
+        let y = |value: f64| MARGIN_TOP + (high - value) / (high - low) * plot_height;
+
+        let currencies = series.iter().map(|(currency, _)| *currency);
+        let legend = legend_layout(currencies, MARGIN_LEFT, width - MARGIN_RIGHT);
+        let legend_rows = legend.last().map_or(1, |(_, row)| row + 1);
+        let total_height = height + (legend_rows - 1) as f64 * LEGEND_ROW_HEIGHT;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = self.width,
+            h = total_height
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+        let mut title = header_description.plain();
+        if header_description.is_inverted() {
+            title.push_str(" (inverted)");
+        }
+        if self.rebase {
+            title.push_str(", index (first date = 100)");
+        }
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="24" font-size="16" font-weight="bold">{}</text>"#,
+            MARGIN_LEFT,
+            escape(&title)
+        );
+
+        // The rate axis, with a grid line per tick
+        let decimals = decimals(ticks[1] - ticks[0]);
+        for tick in ticks.iter() {
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{x1:.1}" y1="{y:.1}" x2="{x2:.1}" y2="{y:.1}" stroke="#e0e0e0"/>"##,
+                x1 = MARGIN_LEFT,
+                x2 = MARGIN_LEFT + plot_width,
+                y = y(*tick)
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{:.decimals$}</text>"#,
+                MARGIN_LEFT - 8.0,
+                y(*tick),
+                tick
+            );
+        }
+
+        // The date axis, with at most six labels
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{x1:.1}" y1="{y:.1}" x2="{x2:.1}" y2="{y:.1}" stroke="#333"/>"##,
+            x1 = MARGIN_LEFT,
+            x2 = MARGIN_LEFT + plot_width,
+            y = MARGIN_TOP + plot_height
+        );
+        let label_count = dates.len().min(6);
+        for label in 0..label_count {
+            let i = match label_count {
+                1 => 0,
+                n => label * (dates.len() - 1) / (n - 1),
+            };
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                x(i),
+                MARGIN_TOP + plot_height + 18.0,
+                escape(&dates[i])
+            );
+        }
+
+        for (index, (_, values)) in series.iter().enumerate() {
+            let color = PALETTE[index % PALETTE.len()];
+            // Dates without a rate break the line, and a rate without neighbours is drawn as a dot
+            let mut path = String::new();
+            let mut pen_down = false;
+            for (i, value) in values.iter().enumerate() {
+                let Some(value) = value else {
+                    pen_down = false;
+                    continue;
+                };
+                let command = if pen_down { 'L' } else { 'M' };
+                let _ = write!(path, "{}{:.1},{:.1} ", command, x(i), y(*value));
+                let has_next = values.get(i + 1).is_some_and(Option::is_some);
+                if !pen_down && !has_next {
+                    let _ = writeln!(
+                        svg,
+                        r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{}"/>"#,
+                        x(i),
+                        y(*value),
+                        color
+                    );
+                }
+                pen_down = true;
+            }
+            let _ = writeln!(
+                svg,
+                r#"<path d="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+                path.trim_end(),
+                color
+            );
+        }
+
+        // The legend, below the date axis
+        for (index, ((currency, _), (legend_x, row))) in series.iter().zip(legend).enumerate() {
+            let legend_y = height - 20.0 + row as f64 * LEGEND_ROW_HEIGHT;
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="14" height="4" fill="{}"/>"#,
+                legend_x,
+                legend_y - 2.0,
+                PALETTE[index % PALETTE.len()]
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" dominant-baseline="middle">{}</text>"#,
+                legend_x + 20.0,
+                legend_y,
+                escape(currency)
+            );
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// The days from the first date to every date, or their index if any of them isn't a date
+fn positions(exchange_rate_results: &[ExchangeRateResult]) -> Vec<f64> {
+    let dates = exchange_rate_results
+        .iter()
+        .map(|x| x.time.parse::<NaiveDate>())
+        .collect::<Result<Vec<_>, _>>();
+    match dates {
+        Ok(dates) if !dates.is_empty() => dates
+            .iter()
+            .map(|date| (*date - dates[0]).num_days() as f64)
+            .collect(),
+        _ => (0..exchange_rate_results.len()).map(|i| i as f64).collect(),
+    }
+}
+
+/// The x and row of every legend entry, starting a new row when the next entry would pass `right`
+fn legend_layout<'a>(
+    currencies: impl Iterator<Item = &'a str>,
+    left: f64,
+    right: f64,
+) -> Vec<(f64, usize)> {
+    let mut layout = Vec::new();
+    let (mut x, mut row) = (left, 0);
+    for currency in currencies {
+        let entry_width = 30.0 + 8.0 * currency.len() as f64;
+        if x > left && x + entry_width > right {
+            x = left;
+            row += 1;
+        }
+        layout.push((x, row));
+        x += entry_width;
+    }
+    layout
+}
+
+/// Round tick values covering `min..=max`, spaced 1, 2 or 5 times a power of ten apart
+fn nice_ticks(min: f64, max: f64, count: usize) -> Vec<f64> {
+    let rough_step = (max - min) / count as f64;
+    let magnitude = 10f64.powf(rough_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|x| x * magnitude)
+        .find(|step| *step >= rough_step)
+        .unwrap_or(10.0 * magnitude);
+
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// How many decimals are needed to tell ticks `step` apart
+fn decimals(step: f64) -> usize {
+    (-step.log10().floor()).max(0.0) as usize
+}
+
+/// Escapes text for XML and HTML
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_nice_ticks() {
+        assert_eq!(
+            nice_ticks(0.0, 10.0, 5),
+            vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]
+        );
+        let ticks = nice_ticks(1.0507, 1.11, 5);
+        assert!(ticks[0] <= 1.0507 && ticks[ticks.len() - 1] >= 1.11);
+        assert_eq!(decimals(0.02), 2);
+        assert_eq!(decimals(5.0), 0);
+    }
+
+    #[test]
+    fn test_rebased_path() {
+        let exchange_rate_results = [
+            ExchangeRateResult::new("2025-01-07".into(), HashMap::from([("USD".into(), 2.0)])),
+            ExchangeRateResult::new("2025-01-08".into(), HashMap::from([("USD".into(), 2.5)])),
+        ];
+        let mut chart = SvgChart::new();
+        chart.rebase = true;
+        let svg = chart.render(
+            &exchange_rate_results,
+            &HeaderDescription::new(),
+            &SortBy::Currency,
+        );
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains("EUR to ALL, index (first date = 100)"));
+        // Rebased from 100 to 125, on ticks from 100 to 125
+        assert!(svg.contains(r#"<path d="M70.0,380.0 L780.0,40.0""#));
+    }
+
+    #[test]
+    fn test_spaced_by_date() {
+        let rates = HashMap::from([("USD".into(), 1.0)]);
+        let exchange_rate_results = ["2025-01-06", "2025-01-07", "2025-01-10"]
+            .map(|date| ExchangeRateResult::new(date.into(), rates.clone()));
+        let svg = SvgChart::new().render(
+            &exchange_rate_results,
+            &HeaderDescription::new(),
+            &SortBy::Currency,
+        );
+        // A quarter of the way, since a weekend and two days lie in between
+        assert!(svg.contains("M70.0,210.0 L247.5,210.0 L780.0,210.0"));
+        assert!(!svg.contains("<circle"));
+    }
+
+    #[test]
+    fn test_single_date() {
+        let rates = (0..30).map(|i| (format!("C{:02}", i).into(), 1.0 + i as f64));
+        let exchange_rate_results = [ExchangeRateResult::new(
+            "2025-01-07".into(),
+            rates.collect(),
+        )];
+        let svg = SvgChart::new().render(
+            &exchange_rate_results,
+            &HeaderDescription::new(),
+            &SortBy::Currency,
+        );
+        assert_eq!(svg.matches("<circle").count(), 30);
+        assert!(svg.contains(r#"<circle cx="425.0""#));
+        // 13 entries fit on a row, so the legend wraps onto three rows which make the image taller
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="486""#)
+        );
+        let layout = legend_layout(["USD"; 30].into_iter(), 70.0, 780.0);
+        assert_eq!(layout[12], (718.0, 0));
+        assert_eq!(layout[13], (70.0, 1));
+    }
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::json;
+
+/// Renders anything serializable as TOML, with the same structure as the JSON output.
+/// TOML documents have to be tables, so a list is put under `key`, e.g. `days` or `currencies`.
+pub fn render<T: Serialize + ?Sized>(value: &T, key: &str, time: bool) -> String {
+    let value = match json::to_value(value, time) {
+        Value::Object(map) => map,
+        value => Map::from_iter([(key.to_string(), value)]),
+    };
+    ::toml::to_string(&value)
+        .expect("Failed to parse content as TOML")
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::exchange_rate_results;
+
+    #[test]
+    fn test_days() {
+        assert_eq!(
+            render(&exchange_rate_results()[..1], "days", true),
+            "[[days]]\ntime = \"2025-01-07\"\n\n[days.rates]\nSEK = 11.5\nUSD = 1.25"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::json;
+
+/// Renders anything serializable as YAML, with the same structure as the JSON output
+pub fn render<T: Serialize + ?Sized>(value: &T, time: bool) -> String {
+    let mut yaml = String::new();
+    match json::to_value(value, time) {
+        value @ (Value::Array(_) | Value::Object(_)) => write_block(&mut yaml, &value, 0),
+        value => yaml.push_str(&scalar(&value)),
+    }
+    yaml.trim_end().to_string()
+}
+
+fn write_block(yaml: &mut String, value: &Value, indent: usize) {
+    let padding = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                if is_block(value) {
+                    yaml.push_str(&format!("{}{}:\n", padding, key_of(key)));
+                    write_block(yaml, value, indent + 2);
+                } else {
+                    yaml.push_str(&format!("{}{}: {}\n", padding, key_of(key), scalar(value)));
+                }
+            }
+        }
+        Value::Array(values) if !values.is_empty() => {
+            for value in values {
+                if is_block(value) {
+                    // The first line of the nested block goes right after the dash
+                    let mut nested = String::new();
+                    write_block(&mut nested, value, indent + 2);
+                    yaml.push_str(&format!("{}- {}", padding, &nested[indent + 2..]));
+                } else {
+                    yaml.push_str(&format!("{}- {}\n", padding, scalar(value)));
+                }
+            }
+        }
+        value => yaml.push_str(&format!("{}{}\n", padding, scalar(value))),
+    }
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(values) => !values.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => false,
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+        // A JSON string is a valid double quoted YAML string
+        Value::String(_) | Value::Bool(_) | Value::Number(_) => value.to_string(),
+    }
+}
+
+/// Keys are left unquoted, unless YAML would read them as anything but a string,
+/// e.g. `NO` or `on`
+fn key_of(key: &str) -> String {
+    const RESERVED: [&str; 11] = [
+        "y", "n", "yes", "no", "on", "off", "true", "false", "null", "~", "",
+    ];
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !RESERVED.contains(&key.to_lowercase().as_str());
+    if plain {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExchangeRateResult;
+    use crate::test_utils::exchange_rate_results;
+
+    #[test]
+    fn test_days() {
+        let mut exchange_rate_results = exchange_rate_results();
+        exchange_rate_results.truncate(1);
+        // Quoted, since YAML 1.1 reads NO as false
+        exchange_rate_results[0].rates.insert("NO".into(), 0.5);
+        assert_eq!(
+            render(&exchange_rate_results, true),
+            "- rates:\n    \"NO\": 0.5\n    SEK: 11.5\n    USD: 1.25\n  time: \"2025-01-07\""
+        );
+        assert_eq!(
+            render(&exchange_rate_results, false),
+            "- rates:\n    \"NO\": 0.5\n    SEK: 11.5\n    USD: 1.25"
+        );
+        assert_eq!(render(&Vec::<ExchangeRateResult>::new(), true), "[]");
+    }
+}
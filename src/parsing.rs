@@ -4,6 +4,7 @@ use quick_xml::Reader;
 use quick_xml::events::Event;
 use smol_str::SmolStr;
 
+use crate::Error;
 use crate::models::ExchangeRateResult;
 
 fn smol_from_utf8(bytes: &[u8]) -> SmolStr {
@@ -12,7 +13,7 @@ fn smol_from_utf8(bytes: &[u8]) -> SmolStr {
         .unwrap_or_else(|_| SmolStr::new(String::from_utf8_lossy(bytes)))
 }
 
-pub fn parse(xml: &str) -> anyhow::Result<Vec<ExchangeRateResult>> {
+pub fn parse(xml: &str) -> crate::Result<Vec<ExchangeRateResult>> {
     let mut reader = Reader::from_str(xml);
     reader.config_mut().trim_text(true);
 
@@ -27,7 +28,7 @@ pub fn parse(xml: &str) -> anyhow::Result<Vec<ExchangeRateResult>> {
         inside_cube_time: &mut bool,
         current_rates: &mut HashMap<SmolStr, f64>,
         results: &mut Vec<ExchangeRateResult>,
-    ) -> anyhow::Result<()> {
+    ) -> crate::Result<()> {
         if e.name().local_name().as_ref() != b"Cube" {
             return Ok(());
         }
@@ -58,10 +59,10 @@ pub fn parse(xml: &str) -> anyhow::Result<Vec<ExchangeRateResult>> {
         if let Some(t) = time_attr {
             if current_time.is_some() {
                 let previous_time = current_time.take().unwrap();
-                results.push(ExchangeRateResult {
-                    time: previous_time,
-                    rates: current_rates.clone(),
-                });
+                results.push(ExchangeRateResult::new(
+                    previous_time,
+                    current_rates.clone(),
+                ));
                 current_rates.clear();
             }
             *current_time = Some(t);
@@ -69,15 +70,18 @@ pub fn parse(xml: &str) -> anyhow::Result<Vec<ExchangeRateResult>> {
         }
 
         if *inside_cube_time && let (Some(c), Some(r_str)) = (currency_attr, rate_attr) {
-            let r = r_str.parse::<f64>()?;
+            let r = r_str.parse::<f64>().map_err(|_| Error::InvalidRate {
+                currency: c.clone(),
+                rate: r_str,
+            })?;
             current_rates.insert(c, r);
         }
 
         Ok(())
     }
 
-    while let Ok(event) = reader.read_event() {
-        match event {
+    loop {
+        match reader.read_event()? {
             Event::Start(e) => {
                 handle_cube_element(
                     &e,
@@ -103,10 +107,7 @@ pub fn parse(xml: &str) -> anyhow::Result<Vec<ExchangeRateResult>> {
     }
 
     if let Some(last_time) = current_time {
-        results.push(ExchangeRateResult {
-            time: last_time,
-            rates: current_rates,
-        });
+        results.push(ExchangeRateResult::new(last_time, current_rates));
     }
 
     Ok(results)
@@ -1,10 +1,12 @@
 mod table_display;
 mod table_getter;
+mod table_matrix;
 mod table_owned;
 mod table_ref;
 mod table_trait;
 
 pub use table_getter::TableGet;
+pub use table_matrix::TableMatrix;
 pub use table_owned::Table;
 pub use table_ref::TableRef;
 pub use table_trait::TableTrait;
@@ -23,11 +23,47 @@ pub fn helper_table_print<T: TableGet>(
         )?;
     }
 
+    // The change columns, with the change of each row and the width of each column
+    let changes = table.get_changes().map(|changes| {
+        let rows = table
+            .get_rows()
+            .iter()
+            .map(|(left, _)| {
+                changes.get(left.as_ref()).map(|change| {
+                    let strings = (
+                        format!("{:+}", change.change),
+                        format!("{:+}", change.change_percent),
+                    );
+                    (strings, change.change)
+                })
+            })
+            .collect::<Vec<_>>();
+        let change_width = rows
+            .iter()
+            .flatten()
+            .map(|((change, _), _)| change.len())
+            .chain(["Change".len()])
+            .max()
+            .unwrap_or_default();
+        let percent_width = rows
+            .iter()
+            .flatten()
+            .map(|((_, percent), _)| percent.len())
+            .chain(["%".len()])
+            .max()
+            .unwrap_or_default();
+        (rows, change_width, percent_width)
+    });
+    let changes_width = changes
+        .as_ref()
+        .map(|(_, change_width, percent_width)| change_width + percent_width + 4)
+        .unwrap_or_default();
+
     let column_left = table.get_column_left();
     let column_right = table.get_column_right();
     let right_padding_amount = width - column_left.len() - column_right.len();
     let right_padding = " ".repeat(right_padding_amount);
-    writeln!(
+    write!(
         f,
         "{}{}{}{}",
         &left_offset,
@@ -35,14 +71,23 @@ pub fn helper_table_print<T: TableGet>(
         right_padding,
         column_right.bold().yellow()
     )?;
-    writeln!(f, "{}{}", &left_offset, "-".repeat(width))?;
+    if let Some((_, change_width, percent_width)) = changes.as_ref() {
+        write!(
+            f,
+            "  {}  {}",
+            format!("{:>change_width$}", "Change").bold().yellow(),
+            format!("{:>percent_width$}", "%").bold().yellow()
+        )?;
+    }
+    writeln!(f)?;
+    writeln!(f, "{}{}", &left_offset, "-".repeat(width + changes_width))?;
 
-    for (left, right) in table.get_rows().iter() {
+    for (i, (left, right)) in table.get_rows().iter().enumerate() {
         let left_str = left.as_ref();
         let right_str = right.to_string();
         let padding_amount = width.saturating_sub(left_str.len() + right_str.len());
         let padding = " ".repeat(padding_amount);
-        writeln!(
+        write!(
             f,
             "{}{}{}{}",
             &left_offset,
@@ -50,6 +95,18 @@ pub fn helper_table_print<T: TableGet>(
             padding,
             right_str
         )?;
+        if let Some((rows, change_width, percent_width)) = changes.as_ref() {
+            let ((change, percent), value) = rows[i].clone().unwrap_or_default();
+            let cells = format!("  {:>change_width$}  {:>percent_width$}", change, percent);
+            if value > 0.0 {
+                write!(f, "{}", cells.green())?;
+            } else if value < 0.0 {
+                write!(f, "{}", cells.red())?;
+            } else {
+                write!(f, "{}", cells)?;
+            }
+        }
+        writeln!(f)?;
     }
 
     Ok(())
@@ -1,3 +1,9 @@
+use std::collections::HashMap;
+
+use smol_str::SmolStr;
+
+use crate::models::RateChange;
+
 pub trait TableGet {
     type RowLeftRef: AsRef<str>;
     type RowRightRef: AsRef<str>;
@@ -6,6 +12,8 @@ pub trait TableGet {
     fn get_column_left(&self) -> &str;
     fn get_column_right(&self) -> &str;
     fn get_rows(&self) -> &Vec<(Self::RowLeftRef, f64)>;
+    /// The change of each row since the previous publication, shown as extra columns
+    fn get_changes(&self) -> Option<&HashMap<SmolStr, RateChange>>;
     fn get_width(&self) -> usize;
     fn get_left_offset(&self) -> usize;
 }
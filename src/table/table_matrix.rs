@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use colored::Colorize;
+use smol_str::SmolStr;
+
+use crate::cli::SortBy;
+use crate::models::ExchangeRateResult;
+
+/// A table with any number of value columns, e.g. currencies as rows and dates as columns
+pub struct TableMatrix {
+    pub(super) corner: SmolStr,
+    pub(super) columns: Vec<SmolStr>,
+    pub(super) rows: Vec<(SmolStr, Vec<Option<f64>>)>,
+    pub left_offset: usize,
+}
+
+impl TableMatrix {
+    /// `corner` names the column of row labels
+    pub fn new(corner: SmolStr, columns: Vec<SmolStr>) -> Self {
+        Self {
+            corner,
+            columns,
+            rows: Vec::new(),
+            left_offset: 1,
+        }
+    }
+
+    pub fn add_row(&mut self, label: SmolStr, values: Vec<Option<f64>>) {
+        assert_eq!(values.len(), self.columns.len());
+        self.rows.push((label, values));
+    }
+
+    /// Sorts the rows by their label, or by their value in the first column
+    pub fn sort(&mut self, sort_by: &SortBy) {
+        match sort_by {
+            SortBy::Currency => self.rows.sort_by(|a, b| a.0.cmp(&b.0)),
+            SortBy::Rate => self.rows.sort_by(|a, b| {
+                let a = a.1.first().copied().flatten().unwrap_or(f64::INFINITY);
+                let b = b.1.first().copied().flatten().unwrap_or(f64::INFINITY);
+                a.total_cmp(&b)
+            }),
+        }
+    }
+
+    /// Swaps the rows and columns. The new `corner` names the previous columns.
+    pub fn transpose(&mut self, corner: SmolStr) {
+        let columns = self.rows.iter().map(|(label, _)| label.clone()).collect();
+        let rows = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let values = self.rows.iter().map(|(_, values)| values[i]).collect();
+                (column.clone(), values)
+            })
+            .collect();
+        *self = Self {
+            corner,
+            columns,
+            rows,
+            left_offset: self.left_offset,
+        };
+    }
+
+    pub fn get_corner(&self) -> &str {
+        &self.corner
+    }
+
+    pub fn get_columns(&self) -> &[SmolStr] {
+        &self.columns
+    }
+
+    pub fn get_rows(&self) -> &[(SmolStr, Vec<Option<f64>>)] {
+        &self.rows
+    }
+}
+
+impl From<&[ExchangeRateResult]> for TableMatrix {
+    /// Currencies as rows, and the dates, or resampled periods, as columns
+    fn from(exchange_rate_results: &[ExchangeRateResult]) -> Self {
+        let columns = exchange_rate_results.iter().map(|x| x.label().into());
+        let mut table = TableMatrix::new("Currency".into(), columns.collect());
+
+        let currencies = exchange_rate_results
+            .iter()
+            .flat_map(|exchange_rate| exchange_rate.rates.keys())
+            .collect::<BTreeSet<&SmolStr>>();
+        for currency in currencies {
+            let values = exchange_rate_results
+                .iter()
+                .map(|exchange_rate| exchange_rate.rates.get(currency).copied())
+                .collect();
+            table.add_row(currency.clone(), values);
+        }
+
+        table
+    }
+}
+
+impl Display for TableMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let left_offset = " ".repeat(self.left_offset);
+        let rows = self
+            .rows
+            .iter()
+            .map(|(label, values)| {
+                let values = values
+                    .iter()
+                    .map(|value| value.map(|x| x.to_string()).unwrap_or_default())
+                    .collect::<Vec<_>>();
+                (label, values)
+            })
+            .collect::<Vec<_>>();
+
+        let label_width = rows
+            .iter()
+            .map(|(label, _)| label.len())
+            .chain([self.corner.len()])
+            .max()
+            .unwrap_or_default();
+        let widths = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                rows.iter()
+                    .map(|(_, values)| values[i].len())
+                    .chain([column.len()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let width = label_width + widths.iter().map(|x| x + 2).sum::<usize>();
+
+        write!(
+            f,
+            "{}{}",
+            &left_offset,
+            format!("{:<label_width$}", self.corner).bold().yellow()
+        )?;
+        for (column, width) in self.columns.iter().zip(widths.iter()) {
+            write!(f, "  {}", format!("{:>width$}", column).bold().yellow())?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{}{}", &left_offset, "-".repeat(width))?;
+
+        for (label, values) in rows {
+            write!(
+                f,
+                "{}{}",
+                &left_offset,
+                format!("{:<label_width$}", label).bold().green()
+            )?;
+            for (value, width) in values.iter().zip(widths.iter()) {
+                write!(f, "  {:>width$}", value)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::strip_colors;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_transpose() {
+        let exchange_rate_results = [
+            ExchangeRateResult::new(
+                "2025-01-07".into(),
+                HashMap::from([("USD".into(), 1.25), ("SEK".into(), 11.5)]),
+            ),
+            ExchangeRateResult::new("2025-01-08".into(), HashMap::from([("USD".into(), 1.5)])),
+        ];
+        let mut table = TableMatrix::from(&exchange_rate_results[..]);
+        assert_eq!(
+            strip_colors(&table.to_string()),
+            " Currency  2025-01-07  2025-01-08\n \
+             --------------------------------\n \
+             SEK             11.5            \n \
+             USD             1.25         1.5\n"
+        );
+
+        table.transpose("Date".into());
+        assert_eq!(
+            strip_colors(&table.to_string()),
+            " Date         SEK   USD\n \
+             ----------------------\n \
+             2025-01-07  11.5  1.25\n \
+             2025-01-08         1.5\n"
+        );
+    }
+}
@@ -1,10 +1,11 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 
 use smol_str::SmolStr;
 
 use crate::DEFAULT_WIDTH;
 use crate::cli::SortBy;
-use crate::models::ExchangeRateResult;
+use crate::models::{ExchangeRateResult, RateChange};
 
 use super::table_display::helper_table_print;
 use super::{TableGet, TableTrait};
@@ -14,6 +15,7 @@ pub struct Table {
     pub(super) column_left: SmolStr,
     pub(super) column_right: SmolStr,
     pub(super) rows: Vec<(SmolStr, f64)>,
+    pub(super) changes: Option<HashMap<SmolStr, RateChange>>,
     pub color: bool,
     pub width: usize,
     pub left_offset: usize,
@@ -35,6 +37,7 @@ impl<'a> TableTrait<'a> for Table {
             column_left,
             column_right,
             rows: Vec::new(),
+            changes: None,
             color: false,
             width: DEFAULT_WIDTH,
             left_offset: 1,
@@ -76,6 +79,9 @@ impl TableGet for Table {
     fn get_rows(&self) -> &Vec<(Self::RowLeftRef, f64)> {
         &self.rows
     }
+    fn get_changes(&self) -> Option<&HashMap<SmolStr, RateChange>> {
+        self.changes.as_ref()
+    }
     fn get_width(&self) -> usize {
         self.width
     }
@@ -87,10 +93,14 @@ impl TableGet for Table {
 
 impl From<ExchangeRateResult> for Table {
     fn from(value: ExchangeRateResult) -> Self {
-        let mut table = Table::new(Some(value.time), "Currency".into(), "Rate".into());
+        let header = value.label().into();
+        let mut table = Table::new(Some(header), "Currency".into(), "Rate".into());
         for (key, val) in value.rates.into_iter() {
             table.add_row(key, val);
         }
+        if !value.changes.is_empty() {
+            table.changes = Some(value.changes);
+        }
 
         table
     }
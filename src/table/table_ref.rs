@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 
+use smol_str::SmolStr;
+
 use crate::DEFAULT_WIDTH;
 use crate::cli::SortBy;
-use crate::models::ExchangeRateResult;
+use crate::models::{ExchangeRateResult, RateChange};
 
 use super::Table;
 use super::table_display::helper_table_print;
@@ -14,6 +17,7 @@ pub struct TableRef<'a> {
     column_left: &'a str,
     column_right: &'a str,
     rows: Vec<(&'a str, f64)>,
+    changes: Option<&'a HashMap<SmolStr, RateChange>>,
     pub color: bool,
     pub width: usize,
     pub left_offset: usize,
@@ -35,6 +39,7 @@ impl<'a> TableTrait<'a> for TableRef<'a> {
             column_left,
             column_right,
             rows: Vec::new(),
+            changes: None,
             color: false,
             width: DEFAULT_WIDTH,
             left_offset: 1,
@@ -75,6 +80,9 @@ impl<'a> TableGet for TableRef<'a> {
     fn get_rows(&self) -> &Vec<(Self::RowLeftRef, f64)> {
         &self.rows
     }
+    fn get_changes(&self) -> Option<&HashMap<SmolStr, RateChange>> {
+        self.changes
+    }
     fn get_width(&self) -> usize {
         self.width
     }
@@ -86,10 +94,13 @@ impl<'a> TableGet for TableRef<'a> {
 
 impl<'a> From<&'a ExchangeRateResult> for TableRef<'a> {
     fn from(value: &'a ExchangeRateResult) -> Self {
-        let mut table = TableRef::new(Some(&value.time), "Currency", "Rate");
+        let mut table = TableRef::new(Some(value.label()), "Currency", "Rate");
         for (key, val) in value.rates.iter() {
             table.add_row(key, *val);
         }
+        if !value.changes.is_empty() {
+            table.changes = Some(&value.changes);
+        }
 
         table
     }
@@ -114,6 +125,7 @@ impl<'a> From<&'a Table> for TableRef<'a> {
             column_left: table.column_left.as_str(),
             column_right: table.column_right.as_str(),
             rows,
+            changes: table.changes.as_ref(),
             color: table.color,
             width: table.width,
             left_offset: table.left_offset,
@@ -0,0 +1,231 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, Weekday};
+
+use crate::{Frankfurt, Hollidays};
+
+/// Calculates the closing days of TARGET, the payment system of the Eurosystem.
+/// The ECB publishes its reference rates on every TARGET business day, which is a different set of
+/// days than the hollidays of the ECB staff, see [Hollidays].
+/// https://www.ecb.europa.eu/paym/target/t2/html/index.en.html
+#[derive(Debug, Clone)]
+pub struct TargetCalendar {
+    closing_days: Vec<NaiveDate>,
+}
+
+impl TargetCalendar {
+    /// The first publication of the euro reference rates
+    pub const FIRST_PUBLICATION: NaiveDate = NaiveDate::from_ymd_opt(1999, 1, 4).unwrap();
+    /// The last year, in which easter and thereby the closing days can be calculated
+    const LAST_YEAR: i32 = 4099;
+
+    pub fn is_closing_day(&self, date: &NaiveDate) -> bool {
+        self.closing_days.contains(date)
+    }
+
+    /// Whether the ECB publishes rates on the given date, e.g. it's a TARGET business day
+    pub fn is_publication_day(date: &NaiveDate) -> bool {
+        let is_weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        date >= &Self::FIRST_PUBLICATION
+            && !is_weekend
+            && Self::new(date.year()).is_some_and(|calendar| !calendar.is_closing_day(date))
+    }
+
+    /// The closest publication day on, or before, the given date.
+    /// None if the date is before the first publication.
+    pub fn last_publication_day(date: NaiveDate) -> Option<NaiveDate> {
+        let last_day = NaiveDate::from_ymd_opt(Self::LAST_YEAR, 12, 31)?;
+        let mut day = date.min(last_day);
+        while !Self::is_publication_day(&day) {
+            if day <= Self::FIRST_PUBLICATION {
+                return None;
+            }
+            day = day - Days::new(1);
+        }
+        Some(day)
+    }
+
+    /// The day of the latest publication at the given time. Rates are published at 16:00, so
+    /// before then it's the publication day before today.
+    /// Clamped to the first publication.
+    pub fn latest_publication_day(now: DateTime<Frankfurt>) -> NaiveDate {
+        let today = now.date_naive();
+        let published_today = now >= Frankfurt::publication_time(today);
+        let date = if published_today {
+            Some(today)
+        } else {
+            today.pred_opt()
+        };
+        date.and_then(Self::last_publication_day)
+            .unwrap_or(Self::FIRST_PUBLICATION)
+    }
+
+    /// The closing days of the year, or None if TARGET didn't exist yet or the year is too far
+    /// into the future to be calculated
+    pub fn new(year: i32) -> Option<Self> {
+        if !(Self::FIRST_PUBLICATION.year()..=Self::LAST_YEAR).contains(&year) {
+            return None;
+        }
+
+        let date =
+            |month, day| unsafe { NaiveDate::from_ymd_opt(year, month, day).unwrap_unchecked() };
+        let new_years_day = date(1, 1);
+        let christmas_day = date(12, 25);
+        let new_years_eve = date(12, 31);
+
+        // TARGET started in 1999, only closing on New Year's Day and Christmas Day,
+        // with an extra closing on New Year's Eve for the millennium change.
+        // The calendar used today was introduced in 2000, with an extra closing on New Year's Eve
+        // 2001 for the introduction of euro banknotes and coins.
+        let closing_days = if year <= 1999 {
+            vec![new_years_day, christmas_day, new_years_eve]
+        } else {
+            let easter_sunday = Hollidays::calc_easter_sunday(year);
+            let good_friday = easter_sunday - Days::new(2);
+            let easter_monday = easter_sunday + Days::new(1);
+            let labour_day = date(5, 1);
+            let christmas_holiday = date(12, 26);
+
+            let mut closing_days = vec![
+                new_years_day,
+                good_friday,
+                easter_monday,
+                labour_day,
+                christmas_day,
+                christmas_holiday,
+            ];
+            if year == 2001 {
+                closing_days.push(new_years_eve);
+            }
+            closing_days
+        };
+
+        Some(Self { closing_days })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::View;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_closing_days_2025() {
+        let calendar = TargetCalendar::new(2025).unwrap();
+        assert!(calendar.is_closing_day(&date(2025, 1, 1)), "New Year's Day");
+        assert!(calendar.is_closing_day(&date(2025, 4, 18)), "Good Friday");
+        assert!(calendar.is_closing_day(&date(2025, 4, 21)), "Easter Monday");
+        assert!(calendar.is_closing_day(&date(2025, 5, 1)), "Labour Day");
+        assert!(
+            calendar.is_closing_day(&date(2025, 12, 25)),
+            "Christmas Day"
+        );
+        assert!(
+            calendar.is_closing_day(&date(2025, 12, 26)),
+            "Christmas Holiday"
+        );
+    }
+
+    #[test]
+    fn test_staff_hollidays_are_publication_days() {
+        let german_unity_day = date(2025, 10, 3);
+        let christmas_eve = date(2025, 12, 24);
+        let new_years_eve = date(2025, 12, 31);
+        assert!(Hollidays::new(2025).is_holliday(&german_unity_day));
+        assert!(TargetCalendar::is_publication_day(&german_unity_day));
+        assert!(TargetCalendar::is_publication_day(&christmas_eve));
+        assert!(TargetCalendar::is_publication_day(&new_years_eve));
+    }
+
+    #[test]
+    fn test_changed_calendars() {
+        assert!(
+            TargetCalendar::is_publication_day(&date(1999, 4, 2)),
+            "Good Friday 1999"
+        );
+        assert!(TargetCalendar::is_publication_day(&date(1999, 5, 3)));
+        assert!(!TargetCalendar::is_publication_day(&date(1999, 12, 31)));
+        assert!(
+            !TargetCalendar::is_publication_day(&date(2000, 4, 21)),
+            "Good Friday 2000"
+        );
+        assert!(TargetCalendar::is_publication_day(&date(2000, 12, 29)));
+        assert!(!TargetCalendar::is_publication_day(&date(2001, 12, 31)));
+        assert!(TargetCalendar::is_publication_day(&date(2002, 12, 31)));
+    }
+
+    #[test]
+    fn test_last_publication_day() {
+        let thursday = date(2024, 3, 28);
+        assert_eq!(
+            TargetCalendar::last_publication_day(thursday),
+            Some(thursday)
+        );
+
+        // Saturday before easter, with good friday in between
+        let saturday = date(2024, 3, 30);
+        assert_eq!(
+            TargetCalendar::last_publication_day(saturday),
+            Some(thursday)
+        );
+
+        let easter_monday = date(2024, 4, 1);
+        assert_eq!(
+            TargetCalendar::last_publication_day(easter_monday),
+            Some(thursday)
+        );
+
+        let new_years_day = date(2025, 1, 1);
+        assert_eq!(
+            TargetCalendar::last_publication_day(new_years_day),
+            Some(date(2024, 12, 31))
+        );
+    }
+
+    #[test]
+    fn test_latest_publication_day() {
+        let at = |date: NaiveDate, hour| {
+            date.and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .and_local_timezone(Frankfurt)
+                .unwrap()
+        };
+        let monday = date(2025, 1, 13);
+        let friday = date(2025, 1, 10);
+        assert_eq!(
+            TargetCalendar::latest_publication_day(at(monday, 15)),
+            friday
+        );
+        assert_eq!(
+            TargetCalendar::latest_publication_day(at(monday, 16)),
+            monday
+        );
+        // Before 16:00 the today feed still holds the publication of friday
+        let latest = TargetCalendar::latest_publication_day(at(monday, 15));
+        assert_eq!(View::covering(friday, latest), View::TODAY);
+        assert_eq!(View::covering(monday, latest), View::TODAY);
+        assert_eq!(
+            TargetCalendar::latest_publication_day(at(date(1500, 1, 1), 12)),
+            TargetCalendar::FIRST_PUBLICATION
+        );
+    }
+
+    #[test]
+    fn test_before_first_publication() {
+        assert!(TargetCalendar::new(1500).is_none());
+        assert!(TargetCalendar::new(9999).is_none());
+        assert!(!TargetCalendar::is_publication_day(&date(1998, 12, 31)));
+        assert_eq!(TargetCalendar::last_publication_day(date(1500, 1, 1)), None);
+        assert_eq!(TargetCalendar::last_publication_day(date(1999, 1, 3)), None);
+        assert_eq!(
+            TargetCalendar::last_publication_day(date(1999, 1, 4)),
+            Some(TargetCalendar::FIRST_PUBLICATION)
+        );
+        assert_eq!(
+            TargetCalendar::last_publication_day(date(9999, 1, 1)),
+            Some(date(4099, 12, 31))
+        );
+    }
+}
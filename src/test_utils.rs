@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::models::ExchangeRateResult;
+
+/// Removes the escape codes of [colored], since whether they are written depends on a global,
+/// which tests running in parallel can't rely on
+pub(crate) fn strip_colors(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+/// A directory of its own for every test, removed when the test is done
+pub(crate) struct TestDir(pub PathBuf);
+
+impl TestDir {
+    pub fn new(name: &str) -> Self {
+        let name = format!("ecb-rates-test-{}-{}", name, std::process::id());
+        Self(std::env::temp_dir().join(name))
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Two publications in chronological order, the second without SEK
+pub(crate) fn exchange_rate_results() -> Vec<ExchangeRateResult> {
+    vec![
+        ExchangeRateResult::new(
+            "2025-01-07".into(),
+            HashMap::from([("USD".into(), 1.25), ("SEK".into(), 11.5)]),
+        ),
+        ExchangeRateResult::new("2025-01-08".into(), HashMap::from([("USD".into(), 1.5)])),
+    ]
+}
@@ -1,8 +1,14 @@
-use std::{borrow::BorrowMut, collections::HashMap, ops::Deref};
+use std::borrow::BorrowMut;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Deref;
 
+use chrono::NaiveDate;
 use smol_str::SmolStr;
 
-use crate::models::ExchangeRateResult;
+use crate::models::{
+    Conversion, ConvertedAmount, Correlation, ExchangeRateResult, RateChange, Stats,
+};
+use crate::{DateRange, Error};
 
 pub fn filter_currencies(exchange_rate_results: &mut [ExchangeRateResult], currencies: &[SmolStr]) {
     for exchange_rate in exchange_rate_results {
@@ -19,15 +25,102 @@ pub fn filter_currencies(exchange_rate_results: &mut [ExchangeRateResult], curre
                 let rates = unsafe { (*rates_ptr).borrow_mut() };
                 rates.remove_entry(key_to_remove);
             });
+        exchange_rate
+            .changes
+            .retain(|currency, _| currencies.contains(currency));
+        exchange_rate
+            .aggregates
+            .retain(|currency, _| currencies.contains(currency));
+    }
+}
+
+pub fn filter_date_range(exchange_rate_results: &mut Vec<ExchangeRateResult>, range: &DateRange) {
+    exchange_rate_results.retain(|exchange_rate| {
+        exchange_rate
+            .time
+            .parse::<NaiveDate>()
+            .is_ok_and(|date| range.contains(&date))
+    });
+}
+
+/// The latest publication on, or before, the given date
+pub fn rate_on_or_before<'a>(
+    exchange_rate_results: &'a [ExchangeRateResult],
+    date: &NaiveDate,
+) -> Option<(NaiveDate, &'a ExchangeRateResult)> {
+    exchange_rate_results
+        .iter()
+        .filter_map(|exchange_rate| {
+            let time = exchange_rate.time.parse::<NaiveDate>().ok()?;
+            (&time <= date).then_some((time, exchange_rate))
+        })
+        .max_by_key(|(time, _)| *time)
+}
+
+/// Adds the change of every rate since the publication before it in `history`. Those previous
+/// publications are first passed to `transform`, e.g. to get them in the same perspective.
+/// A publication without any before it is left without changes.
+pub fn with_changes(
+    history: &[ExchangeRateResult],
+    exchange_rate_results: &mut [ExchangeRateResult],
+    transform: impl FnOnce(&mut [ExchangeRateResult]) -> crate::Result<()>,
+) -> crate::Result<()> {
+    let previous_of = |exchange_rate: &ExchangeRateResult| {
+        let date = exchange_rate.time.parse::<NaiveDate>().ok()?;
+        let (_, previous) = rate_on_or_before(history, &date.pred_opt()?)?;
+        Some(previous)
+    };
+    let mut previous = exchange_rate_results
+        .iter()
+        .filter_map(previous_of)
+        .map(|previous| (previous.time.clone(), previous.clone()))
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect::<Vec<_>>();
+    transform(&mut previous)?;
+
+    for exchange_rate in exchange_rate_results.iter_mut() {
+        let Some(previous_time) = previous_of(exchange_rate).map(|x| x.time.clone()) else {
+            continue;
+        };
+        let Some(previous) = previous.iter().find(|x| x.time == previous_time) else {
+            continue;
+        };
+        exchange_rate.changes = exchange_rate
+            .rates
+            .iter()
+            .filter_map(|(currency, rate)| {
+                let previous = previous.rates.get(currency)?;
+                let change = RateChange {
+                    change: rate - previous,
+                    change_percent: (rate / previous - 1.0) * 100.0,
+                };
+                Some((currency.clone(), change))
+            })
+            .collect();
     }
+    Ok(())
 }
 
 pub fn change_perspective(
     exchange_rate_results: &mut [ExchangeRateResult],
     currency: &str,
-) -> Option<()> {
+) -> crate::Result<()> {
+    let missing_dates = exchange_rate_results
+        .iter()
+        .filter(|rate_res| !rate_res.rates.contains_key(currency))
+        .map(|rate_res| rate_res.time.clone())
+        .collect::<Vec<_>>();
+    if !missing_dates.is_empty() {
+        return Err(Error::UnknownCurrency {
+            currency: currency.into(),
+            missing_dates,
+        });
+    }
+
     for rate_res in exchange_rate_results {
-        let currency_rate = rate_res.rates.remove(currency)?;
+        // This is a safe unwrap, since it was checked above
+        let currency_rate = rate_res.rates.remove(currency).unwrap();
         let eur_rate = 1.0 / currency_rate;
 
         for (_, iter_rate) in rate_res.rates.iter_mut() {
@@ -36,7 +129,7 @@ pub fn change_perspective(
 
         rate_res.rates.insert("EUR".into(), eur_rate);
     }
-    Some(())
+    Ok(())
 }
 
 pub fn invert_rates(exchange_rate_results: &mut [ExchangeRateResult]) {
@@ -47,12 +140,172 @@ pub fn invert_rates(exchange_rate_results: &mut [ExchangeRateResult]) {
     }
 }
 
+/// Converts an amount of the `from` currency into each of the `to` currencies.
+/// Cross rates are calculated through EUR, the same way as [change_perspective]
+pub fn convert(
+    exchange_rate_result: &ExchangeRateResult,
+    amount: f64,
+    from: &str,
+    to: &[SmolStr],
+) -> crate::Result<Conversion> {
+    let mut from_perspective = [exchange_rate_result.clone()];
+    if from != "EUR" {
+        change_perspective(&mut from_perspective, from)?;
+    }
+    let rates = &from_perspective[0].rates;
+
+    let converted = to
+        .iter()
+        .map(|currency| {
+            let rate = if currency == from {
+                1.0
+            } else {
+                *rates.get(currency).ok_or_else(|| Error::UnknownCurrency {
+                    currency: currency.clone(),
+                    missing_dates: vec![exchange_rate_result.time.clone()],
+                })?
+            };
+            Ok(ConvertedAmount {
+                currency: currency.clone(),
+                rate,
+                amount: amount * rate,
+            })
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    Ok(Conversion {
+        time: exchange_rate_result.time.clone(),
+        amount,
+        from: from.into(),
+        to: converted,
+    })
+}
+
 pub fn round(exchange_rate_results: &mut [ExchangeRateResult], max_decimals: u8) {
-    let power = 10.0_f64.powf(max_decimals as f64);
     for rate_res in exchange_rate_results {
         for (_, iter_rate) in rate_res.rates.iter_mut() {
-            let more = iter_rate.deref() * power;
-            *iter_rate = more.round() / power;
+            *iter_rate = round_value(*iter_rate, max_decimals);
         }
+        for (_, change) in rate_res.changes.iter_mut() {
+            change.change = round_value(change.change, max_decimals);
+            change.change_percent = round_value(change.change_percent, max_decimals);
+        }
+        for (_, aggregates) in rate_res.aggregates.iter_mut() {
+            *aggregates = aggregates.map(|x| round_value(x, max_decimals));
+        }
+    }
+}
+
+pub fn round_conversion(conversion: &mut Conversion, max_decimals: u8) {
+    for converted in conversion.to.iter_mut() {
+        converted.rate = round_value(converted.rate, max_decimals);
+        converted.amount = round_value(converted.amount, max_decimals);
+    }
+}
+
+pub fn round_stats(stats: &mut [Stats], max_decimals: u8) {
+    for stats in stats.iter_mut() {
+        for observation in [
+            &mut stats.first,
+            &mut stats.last,
+            &mut stats.min,
+            &mut stats.max,
+        ] {
+            observation.rate = round_value(observation.rate, max_decimals);
+        }
+        stats.mean = round_value(stats.mean, max_decimals);
+        stats.median = round_value(stats.median, max_decimals);
+        stats.stddev = round_value(stats.stddev, max_decimals);
+        stats.change = round_value(stats.change, max_decimals);
+        stats.change_percent = round_value(stats.change_percent, max_decimals);
+    }
+}
+
+pub fn round_correlations(correlations: &mut [Correlation], max_decimals: u8) {
+    for correlation in correlations.iter_mut() {
+        for value in correlation.correlations.values_mut().flatten() {
+            *value = round_value(*value, max_decimals);
+        }
+    }
+}
+
+pub fn round_value(value: f64, max_decimals: u8) -> f64 {
+    let power = 10.0_f64.powf(max_decimals as f64);
+    (value * power).round() / power
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::exchange_rate_results;
+
+    /// The publication with both USD and SEK
+    fn exchange_rate_result() -> ExchangeRateResult {
+        exchange_rate_results().swap_remove(0)
+    }
+
+    #[test]
+    fn test_convert_cross_rate() {
+        let conversion = convert(&exchange_rate_result(), 250.0, "USD", &["SEK".into()]).unwrap();
+        assert!((conversion.to[0].rate - 9.2).abs() < 1e-12);
+        assert!((conversion.to[0].amount - 2300.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_convert_to_and_from_eur() {
+        let to_eur = convert(&exchange_rate_result(), 10.0, "USD", &["EUR".into()]).unwrap();
+        assert_eq!(to_eur.to[0].amount, 8.0);
+
+        let from_eur = convert(&exchange_rate_result(), 10.0, "EUR", &["USD".into()]).unwrap();
+        assert_eq!(from_eur.to[0].amount, 12.5);
+    }
+
+    #[test]
+    fn test_convert_unknown_currency() {
+        assert!(convert(&exchange_rate_result(), 1.0, "XXX", &["SEK".into()]).is_err());
+        assert!(convert(&exchange_rate_result(), 1.0, "USD", &["XXX".into()]).is_err());
+    }
+
+    #[test]
+    fn test_change_perspective_lists_missing_dates() {
+        let mut exchange_rate_results = vec![
+            exchange_rate_result(),
+            ExchangeRateResult::new("2025-01-09".into(), HashMap::from([("SEK".into(), 11.5)])),
+        ];
+        let error = change_perspective(&mut exchange_rate_results, "USD").unwrap_err();
+        assert!(matches!(
+            error,
+            Error::UnknownCurrency { currency, missing_dates }
+                if currency == "USD" && missing_dates == ["2025-01-09"]
+        ));
+        // Nothing is changed, when the currency is missing
+        assert_eq!(exchange_rate_results[0], exchange_rate_result());
+    }
+
+    #[test]
+    fn test_with_changes() {
+        let history = vec![
+            exchange_rate_result(),
+            ExchangeRateResult::new(
+                "2025-01-06".into(),
+                HashMap::from([("USD".into(), 1.0), ("SEK".into(), 11.5)]),
+            ),
+        ];
+        let mut exchange_rate_results = vec![exchange_rate_result()];
+        with_changes(&history, &mut exchange_rate_results, |previous| {
+            assert_eq!(previous.len(), 1);
+            invert_rates(previous);
+            Ok(())
+        })
+        .unwrap();
+        let changes = &exchange_rate_results[0].changes;
+        assert_eq!(changes["USD"].change, 0.25);
+        assert_eq!(changes["USD"].change_percent, 25.0);
+        assert!((changes["SEK"].change - (11.5 - 1.0 / 11.5)).abs() < 1e-12);
+
+        // Nothing was published before the first one
+        let mut first = vec![history[1].clone()];
+        with_changes(&history, &mut first, |_| Ok(())).unwrap();
+        assert!(first[0].changes.is_empty());
     }
 }
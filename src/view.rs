@@ -1,8 +1,17 @@
+use chrono::{NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+
 use crate::ecb_url;
 
+/// The feeds published by the ECB, ordered from the smallest to the largest.
+/// Every feed contains all the publications of the smaller ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum View {
+    #[serde(rename = "today")]
     TODAY,
+    #[serde(rename = "last-90-days")]
     HistDays90,
+    #[serde(rename = "all-days")]
     HistDaysAll,
 }
 
@@ -22,4 +31,25 @@ impl View {
             Self::HistDaysAll => "all-days",
         }
     }
+
+    /// The smallest view which still contains every publication from `from` on, when the latest
+    /// publication was on `latest`, see [crate::TargetCalendar::latest_publication_day]
+    pub fn covering(from: NaiveDate, latest: NaiveDate) -> Self {
+        if from >= latest {
+            Self::TODAY
+        } else if from > latest - TimeDelta::days(90) {
+            Self::HistDays90
+        } else {
+            Self::HistDaysAll
+        }
+    }
+
+    /// The oldest date contained in the view, when the latest publication was on `latest`
+    pub fn first_date(&self, latest: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::TODAY => Some(latest),
+            Self::HistDays90 => Some(latest - TimeDelta::days(89)),
+            Self::HistDaysAll => None,
+        }
+    }
 }